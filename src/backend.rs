@@ -0,0 +1,191 @@
+use std::cell::RefCell;
+use std::io;
+use std::io::Write;
+use std::rc::Rc;
+
+use crossterm::terminal;
+
+use crate::config::Config;
+use crate::editor_output::EditorOutput;
+use crate::screen::{Frame, Screen};
+use crate::terminal_guard;
+
+/// 终端后端。
+///
+/// 编辑视图通过后端获取窗口大小并输出每一帧画面。运行时使用 [`CrosstermBackend`] 输出至终端，
+/// 测试时使用 [`HeadlessBackend`] 将画面保存在内存中
+pub trait Backend {
+    /// 进入编辑状态
+    fn enter(&mut self) -> io::Result<()>;
+
+    /// 获取窗口大小 (列数, 行数)
+    fn size(&self) -> io::Result<(usize, usize)>;
+
+    /// 输出一帧画面
+    fn draw(&mut self, frame: Frame) -> io::Result<()>;
+
+    /// 使已输出的画面失效，下一次输出时完整重绘
+    fn invalidate(&mut self);
+
+    /// 挂起编辑器，将终端交还 shell，恢复运行后返回
+    fn suspend(&mut self) -> io::Result<()>;
+
+    /// 进程被外部暂停后继续运行时，重新进入编辑状态
+    fn resume(&mut self) -> io::Result<()>;
+}
+
+/// 终端后端，仅输出与上一帧不同的单元格。销毁时恢复终端
+pub struct CrosstermBackend {
+    // 编辑器输出
+    output: EditorOutput,
+    // 影子屏幕，用于差异输出
+    screen: Screen,
+    // 是否使用备用屏幕
+    alternate_screen: bool,
+    // 终端支持时是否使用同步更新
+    synchronized_output: bool,
+    // 是否已进入编辑状态
+    entered: bool,
+}
+
+impl CrosstermBackend {
+    /// 根据配置创建终端后端，进入编辑状态前不修改终端
+    pub fn new(config: &Config) -> Self {
+        Self {
+            output: EditorOutput::new(),
+            screen: Screen::new(),
+            alternate_screen: config.alternate_screen(),
+            synchronized_output: config.synchronized_output(),
+            entered: false,
+        }
+    }
+}
+
+impl Backend for CrosstermBackend {
+    fn enter(&mut self) -> io::Result<()> {
+        terminal_guard::enter_terminal(self.alternate_screen)?;
+        self.entered = true;
+        // 查询需要在 Raw 模式下进行，终端的响应才不会回显到屏幕上
+        if self.synchronized_output {
+            let supported = terminal_guard::supports_synchronized_output();
+            info!("终端支持同步更新：{}", supported);
+            self.output.set_synchronized(supported);
+        }
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<(usize, usize)> {
+        terminal::size().map(|(x, y)| (x as usize, y as usize))
+    }
+
+    fn draw(&mut self, frame: Frame) -> io::Result<()> {
+        if self.screen.draw(frame, &mut self.output)? {
+            self.output.flush()?;
+        }
+        Ok(())
+    }
+
+    fn invalidate(&mut self) {
+        self.screen.invalidate();
+    }
+
+    fn suspend(&mut self) -> io::Result<()> {
+        terminal_guard::suspend()
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        terminal_guard::resume()
+    }
+}
+
+impl Drop for CrosstermBackend {
+    fn drop(&mut self) {
+        if !self.entered {
+            return;
+        }
+        if let Err(e) = terminal_guard::leave_terminal() {
+            error!("无法恢复终端：{}", e);
+        }
+    }
+}
+
+/// 内存后端，保存最近输出的一帧画面。
+///
+/// 克隆得到的后端共享同一帧画面，将其中一个交给编辑器后，可通过另一个读取渲染结果
+#[derive(Clone)]
+pub struct HeadlessBackend {
+    // 窗口大小
+    size: (usize, usize),
+    // 最近输出的一帧画面
+    frame: Rc<RefCell<Frame>>,
+}
+
+impl HeadlessBackend {
+    /// 创建指定大小的内存后端
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            size: (width, height),
+            frame: Rc::new(RefCell::new(Frame::new(width, height))),
+        }
+    }
+
+    /// 获取指定行的文本，省略行尾空白
+    pub fn row_text(&self, y: usize) -> String {
+        self.frame.borrow().row_text(y)
+    }
+
+    /// 获取光标位置 (列, 行)
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.frame.borrow().cursor()
+    }
+
+    /// 获取状态栏文本
+    pub fn status_bar(&self) -> String {
+        self.row_text(self.size.1.saturating_sub(2))
+    }
+
+    /// 获取消息栏文本
+    pub fn message_bar(&self) -> String {
+        self.row_text(self.size.1.saturating_sub(1))
+    }
+
+    /// 生成画面快照：逐行输出文本，最后一行为光标位置
+    pub fn snapshot(&self) -> String {
+        let frame = self.frame.borrow();
+        let mut snapshot = String::new();
+        for y in 0..self.size.1 {
+            snapshot.push_str(&frame.row_text(y));
+            snapshot.push('\n');
+        }
+        match frame.cursor() {
+            Some((x, y)) => snapshot.push_str(&format!("cursor: ({}, {})\n", x, y)),
+            None => snapshot.push_str("cursor: hidden\n"),
+        }
+        snapshot
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn enter(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn size(&self) -> io::Result<(usize, usize)> {
+        Ok(self.size)
+    }
+
+    fn draw(&mut self, frame: Frame) -> io::Result<()> {
+        *self.frame.borrow_mut() = frame;
+        Ok(())
+    }
+
+    fn invalidate(&mut self) {}
+
+    fn suspend(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn resume(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,395 @@
+use std::borrow::Cow;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use ropey::{Rope, RopeSlice};
+
+use crate::encoding::FileEncoding;
+use crate::layout::Layout;
+use crate::mapped_file::MappedFile;
+
+/// 文本缓冲区。
+///
+/// 可编辑的内容基于 rope 实现，插入与删除的时间复杂度为 O(log n)；超大文件以内存映射方式只读打开。
+/// 文本按行访问，行内位置均为不包含换行符的字节偏移量。光标移动见 [`crate::motion`]。
+/// 克隆时共享文本内容，时间复杂度为 O(1)
+#[derive(Clone)]
+pub struct TextBuffer {
+    // 文本内容
+    content: Content,
+    // 换行符风格
+    line_ending: LineEnding,
+    // 制表符与显示宽度策略
+    layout: Layout,
+}
+
+/// 换行符风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// `\n`
+    Lf,
+    /// `\r\n`
+    Crlf,
+    /// 同时存在 `\n` 与 `\r\n`
+    Mixed,
+}
+
+impl LineEnding {
+    /// 检测文本中使用的换行符风格，不包含换行符时视为 LF
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        match (lf, crlf) {
+            (_, 0) => LineEnding::Lf,
+            (0, _) => LineEnding::Crlf,
+            _ => LineEnding::Mixed,
+        }
+    }
+
+    /// 根据名称获取换行符风格，仅支持 `lf` 与 `crlf`
+    pub fn for_label(label: &str) -> Option<Self> {
+        match label.trim().to_ascii_lowercase().as_str() {
+            "lf" | "unix" => Some(LineEnding::Lf),
+            "crlf" | "dos" => Some(LineEnding::Crlf),
+            _ => None,
+        }
+    }
+
+    /// 获取换行符风格名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::Crlf => "CRLF",
+            LineEnding::Mixed => "Mixed",
+        }
+    }
+
+    // 新增换行时使用的换行符，混合风格时使用 LF
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Lf | LineEnding::Mixed => "\n",
+        }
+    }
+}
+
+/// 对只读缓冲区执行编辑操作时返回的错误
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReadOnlyError;
+
+impl Display for ReadOnlyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "只读缓冲区不支持编辑")
+    }
+}
+
+impl std::error::Error for ReadOnlyError {}
+
+/// 编辑操作结果，只读缓冲区拒绝编辑
+pub type EditResult = std::result::Result<(), ReadOnlyError>;
+
+/// 文本内容存储方式
+#[derive(Clone)]
+enum Content {
+    /// 可编辑文本
+    Rope(Rope),
+    /// 内存映射的只读文件
+    Mapped(MappedFile),
+}
+
+impl Default for TextBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextBuffer {
+    /// 创建空文本缓冲区
+    pub fn new() -> Self {
+        Self {
+            content: Content::Rope(Rope::new()),
+            line_ending: LineEnding::Lf,
+            layout: Layout::default(),
+        }
+    }
+
+    /// 根据文本内容创建缓冲区，换行符保持原样
+    pub fn from_text(text: &str) -> Self {
+        Self {
+            content: Content::Rope(Rope::from_str(text)),
+            line_ending: LineEnding::detect(text),
+            layout: Layout::default(),
+        }
+    }
+
+    /// 以内存映射方式创建只读缓冲区
+    pub fn from_mapped_file(file: MappedFile) -> Self {
+        Self {
+            content: Content::Mapped(file),
+            line_ending: LineEnding::Lf,
+            layout: Layout::default(),
+        }
+    }
+
+    /// 是否为只读缓冲区，只读缓冲区的编辑操作均返回 [`ReadOnlyError`]
+    pub fn is_read_only(&self) -> bool {
+        matches!(self.content, Content::Mapped(_))
+    }
+
+    /// 行索引是否仍在建立中，仅内存映射的缓冲区存在该状态
+    pub fn is_indexing(&self) -> bool {
+        match &self.content {
+            Content::Rope(_) => false,
+            Content::Mapped(file) => file.is_indexing(),
+        }
+    }
+
+    /// 检查内存映射的文件是否已被截断，仅在首次检测到截断时返回 true
+    pub fn check_truncation(&self) -> bool {
+        match &self.content {
+            Content::Rope(_) => false,
+            Content::Mapped(file) => file.check_truncation(),
+        }
+    }
+
+    /// 内存映射的文件是否已被截断，截断后所有行均为空行
+    pub fn is_truncated(&self) -> bool {
+        match &self.content {
+            Content::Rope(_) => false,
+            Content::Mapped(file) => file.is_truncated(),
+        }
+    }
+
+    /// 将文本内容写入输出流，返回写入的字节数。只读缓冲区不支持写出
+    pub fn write_to(&self, writer: impl Write) -> io::Result<usize> {
+        let rope = self
+            .rope()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, ReadOnlyError))?;
+        rope.write_to(writer)?;
+        Ok(rope.len_bytes())
+    }
+
+    /// 以指定编码将文本内容写入输出流，返回写入的字节数
+    pub fn write_encoded(
+        &self,
+        mut writer: impl Write,
+        encoding: &FileEncoding,
+    ) -> io::Result<usize> {
+        if encoding.is_plain_utf8() {
+            return self.write_to(writer);
+        }
+        let text = self
+            .text()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, ReadOnlyError))?;
+        let bytes = encoding.encode(&text)?;
+        writer.write_all(&bytes)?;
+        Ok(bytes.len())
+    }
+
+    /// 以指定编码将文本内容保存至指定文件，返回写入的字节数
+    pub fn save(&self, path: &Path, encoding: &FileEncoding) -> io::Result<usize> {
+        if encoding.is_plain_utf8() {
+            let mut writer = BufWriter::new(File::create(path)?);
+            let len = self.write_to(&mut writer)?;
+            writer.flush()?;
+            return Ok(len);
+        }
+        // 先完成编码再创建文件，避免编码失败时清空原文件
+        let mut bytes = Vec::new();
+        self.write_encoded(&mut bytes, encoding)?;
+        fs::write(path, &bytes)?;
+        Ok(bytes.len())
+    }
+
+    /// 获取制表符与显示宽度策略
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// 设置制表符与显示宽度策略
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+
+    /// 获取换行符风格
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// 文本是否以换行符结尾，空文本视为以换行符结尾
+    pub fn has_final_newline(&self) -> bool {
+        match &self.content {
+            Content::Rope(rope) => rope.len_bytes() == 0 || Self::ends_with_line_break(rope),
+            Content::Mapped(_) => true,
+        }
+    }
+
+    /// 将所有换行符转换为指定风格
+    pub fn convert_line_ending(&mut self, line_ending: LineEnding) -> Result<(), ReadOnlyError> {
+        let rope = self.rope_mut()?;
+        let text = String::from(&*rope).replace("\r\n", "\n");
+        let text = match line_ending {
+            LineEnding::Crlf => text.replace('\n', "\r\n"),
+            LineEnding::Lf | LineEnding::Mixed => text,
+        };
+        *rope = Rope::from_str(&text);
+        self.line_ending = line_ending;
+        Ok(())
+    }
+
+    /// 获取全部文本内容，内存映射的只读缓冲区返回 None
+    pub fn text(&self) -> Option<String> {
+        self.rope().map(String::from)
+    }
+
+    /// 获取文本总行数，文本末尾换行符之后的空行不计入
+    pub fn number_of_rows(&self) -> usize {
+        let rope = match &self.content {
+            Content::Rope(rope) => rope,
+            Content::Mapped(file) => return file.number_of_rows(),
+        };
+        if rope.len_bytes() == 0 {
+            0
+        } else if Self::ends_with_line_break(rope) {
+            rope.len_lines() - 1
+        } else {
+            rope.len_lines()
+        }
+    }
+
+    /// 获取指定行不包含换行符的原内容
+    ///
+    /// # Panics
+    ///
+    /// 行号不小于总行数时可能 panic
+    pub fn row(&self, i: usize) -> Cow<'_, str> {
+        match &self.content {
+            Content::Rope(rope) => Self::row_slice(rope, i).into(),
+            Content::Mapped(file) => file.row(i),
+        }
+    }
+
+    /// 在指定行的指定字节位置插入文本，行号等于总行数时在文本末尾追加新行。
+    /// 字节位置位于字符中间时在该字符之前插入
+    ///
+    /// # Panics
+    ///
+    /// 行号大于总行数，或字节位置超出文本末尾时 panic
+    pub fn insert(&mut self, row: usize, x: usize, text: &str) -> Result<(), ReadOnlyError> {
+        self.ensure_row(row)?;
+        let rope = self.rope_mut()?;
+        let char_idx = Self::char_index(rope, row, x);
+        rope.insert(char_idx, text);
+        Ok(())
+    }
+
+    /// 删除指定行内指定字节范围的内容，范围端点位于字符中间时取该字符的起始位置
+    ///
+    /// # Panics
+    ///
+    /// 行号大于总行数，范围端点超出文本末尾，或范围起点大于终点时 panic
+    pub fn remove(&mut self, row: usize, range: Range<usize>) -> Result<(), ReadOnlyError> {
+        let rope = self.rope_mut()?;
+        let start = Self::char_index(rope, row, range.start);
+        let end = Self::char_index(rope, row, range.end);
+        rope.remove(start..end);
+        Ok(())
+    }
+
+    /// 在指定行的指定字节位置插入换行符，将该行拆分为两行。
+    ///
+    /// 该行已有换行符时沿用其换行符，否则使用缓冲区的换行符风格
+    ///
+    /// # Panics
+    ///
+    /// 与 [`TextBuffer::insert`] 相同
+    pub fn split_row(&mut self, row: usize, x: usize) -> Result<(), ReadOnlyError> {
+        let rope = self.rope().ok_or(ReadOnlyError)?;
+        let line_break = if row < self.number_of_rows() {
+            Self::row_line_break(rope, row)
+        } else {
+            None
+        };
+        let line_break = line_break.unwrap_or(self.line_ending.as_str());
+        self.insert(row, x, line_break)
+    }
+
+    /// 删除指定行末尾的换行符，将下一行拼接到该行末尾
+    ///
+    /// # Panics
+    ///
+    /// 行号不小于总行数时可能 panic
+    pub fn join_with_next_row(&mut self, row: usize) -> Result<(), ReadOnlyError> {
+        let rope = self.rope_mut()?;
+        let line_end = rope.line_to_char(row + 1);
+        let break_len = rope.line(row).len_chars() - Self::row_slice(rope, row).len_chars();
+        rope.remove(line_end - break_len..line_end);
+        Ok(())
+    }
+
+    // 获取指定行末尾的换行符，最后一行不以换行符结尾时返回 None
+    fn row_line_break(rope: &Rope, row: usize) -> Option<&'static str> {
+        let break_len = rope.line(row).len_chars() - Self::row_slice(rope, row).len_chars();
+        match break_len {
+            2 => Some("\r\n"),
+            1 => Some("\n"),
+            _ => None,
+        }
+    }
+
+    // 获取指定行不包含换行符的内容切片
+    fn row_slice(rope: &Rope, i: usize) -> RopeSlice<'_> {
+        let line = rope.line(i);
+        let len = line.len_chars();
+        let break_len = if line.chars_at(len).prev() != Some('\n') {
+            0
+        } else if len >= 2 && line.char(len - 2) == '\r' {
+            2
+        } else {
+            1
+        };
+        line.slice(..len - break_len)
+    }
+
+    // 行号等于总行数时，确保文本以换行符结尾，使得新内容位于新的一行
+    fn ensure_row(&mut self, row: usize) -> Result<(), ReadOnlyError> {
+        let at_end = row == self.number_of_rows();
+        let line_break = self.line_ending.as_str();
+        let rope = self.rope_mut()?;
+        if at_end && rope.len_bytes() > 0 && !Self::ends_with_line_break(rope) {
+            rope.insert(rope.len_chars(), line_break);
+        }
+        Ok(())
+    }
+
+    // 文本是否以换行符结尾
+    fn ends_with_line_break(rope: &Rope) -> bool {
+        rope.len_chars() > 0 && rope.char(rope.len_chars() - 1) == '\n'
+    }
+
+    // 将行内字节位置转换为 rope 中的字符位置
+    fn char_index(rope: &Rope, row: usize, x: usize) -> usize {
+        let line_start = rope.line_to_byte(row);
+        rope.byte_to_char(line_start + x)
+    }
+
+    // 获取可编辑文本，内存映射的只读缓冲区返回 None
+    fn rope(&self) -> Option<&Rope> {
+        match &self.content {
+            Content::Rope(rope) => Some(rope),
+            Content::Mapped(_) => None,
+        }
+    }
+
+    fn rope_mut(&mut self) -> Result<&mut Rope, ReadOnlyError> {
+        match &mut self.content {
+            Content::Rope(rope) => Ok(rope),
+            Content::Mapped(_) => Err(ReadOnlyError),
+        }
+    }
+}
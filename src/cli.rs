@@ -0,0 +1,209 @@
+use std::path::{Path, PathBuf};
+
+use log::LevelFilter;
+
+/// 命令行用法说明
+pub const USAGE: &str = "\
+用法：z-editor [选项] [+行号] [文件[:行[:列]]]...
+
+参数：
+  文件                  要打开的文件，可指定多个，编辑时使用 Alt-N/Alt-P 切换
+  文件:行[:列]          打开文件并将光标移动到指定位置，行号与列号从 1 开始，列号按字符计
+  +行号                 将光标移动到下一个文件的指定行
+  -                     从标准输入读取内容，未指定文件且内容通过管道输入时同样从标准输入读取
+
+选项：
+  -R, --read-only       以只读模式打开文件
+      --config <路径>   使用指定的配置文件
+      --log-file <路径> 将日志写入指定文件，默认写入 $XDG_STATE_HOME/z-editor/z-editor.log
+      --log-level <级别>
+                        日志级别：off、error、warn、info、debug 或 trace，
+                        未指定时使用环境变量 RUST_LOG，默认为 info
+      --replay <脚本>   在虚拟窗口中回放按键脚本，输出最终的文本内容与画面
+  -h, --help            显示帮助信息并退出
+  -V, --version         显示版本号并退出
+      --                之后的参数均视为文件名";
+
+/// 命令行参数解析结果
+#[derive(Debug, PartialEq, Eq)]
+pub enum Invocation {
+    /// 按选项打开文件进行编辑
+    Edit(Options),
+    /// 显示帮助信息
+    Help,
+    /// 显示版本号
+    Version,
+}
+
+/// 命令行中指定的文件
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileArg {
+    // 文件路径，None 表示标准输入
+    path: Option<PathBuf>,
+    // 打开后光标所在的行号，从 1 开始
+    line: Option<usize>,
+    // 打开后光标所在的列号，从 1 开始，按字符计
+    column: Option<usize>,
+}
+
+impl FileArg {
+    /// 创建文件参数，`path` 为 None 时表示标准输入
+    pub fn new(path: Option<PathBuf>, line: Option<usize>, column: Option<usize>) -> Self {
+        Self { path, line, column }
+    }
+
+    /// 获取文件路径，None 表示标准输入
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 获取打开后光标所在的行号，从 1 开始
+    pub fn line(&self) -> Option<usize> {
+        self.line
+    }
+
+    /// 获取打开后光标所在的列号，从 1 开始，按字符计
+    pub fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    // 解析文件参数。路径本身存在时按原样使用，否则尝试拆分末尾的 :行 或 :行:列
+    fn parse(arg: &str) -> Self {
+        if arg == "-" {
+            return Self::new(None, None, None);
+        }
+        if !Path::new(arg).exists() {
+            let mut parts = arg.rsplitn(3, ':');
+            let last = parts.next().and_then(Self::parse_number);
+            let middle = parts.next();
+            let rest = parts.next();
+            match (last, middle, rest) {
+                (Some(column), Some(line), Some(path)) if !path.is_empty() => {
+                    if let Some(line) = Self::parse_number(line) {
+                        return Self::new(Some(path.into()), Some(line), Some(column));
+                    }
+                }
+                _ => {}
+            }
+            if let Some((path, line)) = arg.rsplit_once(':') {
+                if let (Some(line), false) = (Self::parse_number(line), path.is_empty()) {
+                    return Self::new(Some(path.into()), Some(line), None);
+                }
+            }
+        }
+        Self::new(Some(arg.into()), None, None)
+    }
+
+    // 解析从 1 开始的行号或列号
+    fn parse_number(text: &str) -> Option<usize> {
+        text.parse().ok().filter(|&n| n > 0)
+    }
+}
+
+/// 编辑选项
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    // 要打开的文件
+    files: Vec<FileArg>,
+    // 是否以只读模式打开
+    read_only: bool,
+    // 配置文件路径
+    config: Option<PathBuf>,
+    // 日志文件路径
+    log_file: Option<PathBuf>,
+    // 日志级别
+    log_level: Option<LevelFilter>,
+    // 按键脚本路径
+    replay: Option<PathBuf>,
+}
+
+impl Options {
+    /// 获取要打开的文件
+    pub fn files(&self) -> &[FileArg] {
+        &self.files
+    }
+
+    /// 是否以只读模式打开
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// 获取配置文件路径，未指定时使用默认位置
+    pub fn config(&self) -> Option<&Path> {
+        self.config.as_deref()
+    }
+
+    /// 获取日志文件路径
+    pub fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
+    /// 获取日志级别，未指定时使用环境变量 RUST_LOG
+    pub fn log_level(&self) -> Option<LevelFilter> {
+        self.log_level
+    }
+
+    /// 获取按键脚本路径，指定时回放脚本而不进入终端
+    pub fn replay(&self) -> Option<&Path> {
+        self.replay.as_deref()
+    }
+}
+
+/// 解析命令行参数，不包含程序名。出错时返回错误信息
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Invocation, String> {
+    let mut options = Options::default();
+    let mut args = args.into_iter();
+    // 下一个文件的行号
+    let mut pending_line = None;
+    let mut only_files = false;
+    while let Some(arg) = args.next() {
+        if only_files || arg == "-" || !arg.starts_with(['-', '+']) {
+            let mut file = FileArg::parse(&arg);
+            if file.path.is_none() && options.files.iter().any(|file| file.path.is_none()) {
+                return Err("只能从标准输入读取一次".to_string());
+            }
+            if let Some(line) = pending_line.take() {
+                file.line = Some(line);
+                file.column = None;
+            }
+            options.files.push(file);
+            continue;
+        }
+        if let Some(line) = arg.strip_prefix('+') {
+            let line = FileArg::parse_number(line).ok_or_else(|| format!("无效的行号：{}", arg))?;
+            pending_line = Some(line);
+            continue;
+        }
+        let (name, inline_value) = match arg.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (arg.as_str(), None),
+        };
+        let mut value = |name: &str| {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("选项 {} 缺少参数", name))
+        };
+        match name {
+            "-h" | "--help" => return Ok(Invocation::Help),
+            "-V" | "--version" => return Ok(Invocation::Version),
+            "-R" | "--read-only" => options.read_only = true,
+            "--config" => options.config = Some(value(name)?.into()),
+            "--log-file" => options.log_file = Some(value(name)?.into()),
+            "--log-level" => {
+                let level = value(name)?;
+                let level = level
+                    .parse()
+                    .map_err(|_| format!("无效的日志级别：{}", level))?;
+                options.log_level = Some(level);
+            }
+            "--replay" => options.replay = Some(value(name)?.into()),
+            "--" => only_files = true,
+            _ => return Err(format!("无法识别的选项：{}", arg)),
+        }
+    }
+    if pending_line.is_some() {
+        return Err("+行号 之后缺少文件".to_string());
+    }
+    Ok(Invocation::Edit(options))
+}
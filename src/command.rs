@@ -0,0 +1,154 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// 光标移动方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Motion {
+    /// 上移一行，软换行模式下上移一个屏幕行
+    Up,
+    /// 下移一行，软换行模式下下移一个屏幕行
+    Down,
+    /// 左移一个字形簇，位于行首时移动至上一行行末
+    Left,
+    /// 右移一个字形簇，位于行末时移动至下一行行首
+    Right,
+    /// 移动至行首，软换行模式下移动至当前分段开头
+    Home,
+    /// 移动至行末，软换行模式下移动至当前分段末尾
+    End,
+    /// 上翻一页
+    PageUp,
+    /// 下翻一页
+    PageDown,
+    /// 移动至下一个单词开头，中日韩文字逐字移动
+    WordForward,
+    /// 移动至当前或上一个单词开头
+    WordBackward,
+    /// 移动至下一个段落之后的空白行
+    ParagraphForward,
+    /// 移动至上一个段落之前的空白行
+    ParagraphBackward,
+    /// 移动至文件开头
+    BufferStart,
+    /// 移动至文件末尾
+    BufferEnd,
+    /// 移动至光标处括号的匹配括号
+    MatchingBracket,
+}
+
+/// 编辑器命令。
+///
+/// 按键先由 [`Command::from_key`] 映射为命令，再交给 [`Editor::execute`](crate::Editor::execute) 执行，
+/// 嵌入编辑器的程序也可以直接执行命令
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Command {
+    /// 退出编辑器
+    Quit,
+    /// 保存文件
+    Save,
+    /// 挂起编辑器并交还 shell
+    Suspend,
+    /// 移动光标
+    Move(Motion),
+    /// 在光标处插入字符，十六进制视图下改写光标处的半字节
+    InsertChar(char),
+    /// 在光标处插入换行
+    InsertNewline,
+    /// 删除光标前的字形簇
+    DeleteBackward,
+    /// 删除光标处的字形簇
+    DeleteForward,
+    /// 切换绝对行号与相对行号
+    ToggleLineNumbers,
+    /// 切换软换行
+    ToggleSoftWrap,
+    /// 开启或关闭十六进制编辑
+    ToggleHexEditing,
+    /// 以指定编码重新打开文件
+    ReopenWithEncoding,
+    /// 转换保存时使用的编码
+    ConvertEncoding,
+    /// 转换换行符
+    ConvertLineEnding,
+    /// 切换至命令行中指定的下一个文件
+    NextFile,
+    /// 切换至命令行中指定的上一个文件
+    PreviousFile,
+    /// 打开或关闭日志查看器
+    ShowLog,
+    /// 在消息栏中输入位置并跳转
+    GotoLine,
+}
+
+impl Command {
+    /// 将按键映射为命令，没有对应命令的按键返回 None
+    pub fn from_key(event: KeyEvent) -> Option<Self> {
+        let command = match (event.code, event.modifiers) {
+            (KeyCode::Char('q'), KeyModifiers::CONTROL) => Self::Quit,
+            (KeyCode::Char('s'), KeyModifiers::CONTROL) => Self::Save,
+            (KeyCode::Char('z'), KeyModifiers::CONTROL) => Self::Suspend,
+            (KeyCode::Char('n'), KeyModifiers::CONTROL) => Self::ToggleLineNumbers,
+            (KeyCode::Char('e'), KeyModifiers::CONTROL) => Self::ToggleHexEditing,
+            (KeyCode::Char('h'), KeyModifiers::CONTROL) => Self::DeleteBackward,
+            (KeyCode::Char('g'), KeyModifiers::CONTROL) => Self::GotoLine,
+            (KeyCode::Char('z'), KeyModifiers::ALT) => Self::ToggleSoftWrap,
+            (KeyCode::Char('r'), KeyModifiers::ALT) => Self::ReopenWithEncoding,
+            (KeyCode::Char('e'), KeyModifiers::ALT) => Self::ConvertEncoding,
+            (KeyCode::Char('l'), KeyModifiers::ALT) => Self::ConvertLineEnding,
+            (KeyCode::Char('m'), KeyModifiers::ALT) => Self::Move(Motion::MatchingBracket),
+            (KeyCode::Char('n'), KeyModifiers::ALT) => Self::NextFile,
+            (KeyCode::Char('p'), KeyModifiers::ALT) => Self::PreviousFile,
+            (KeyCode::F(12), KeyModifiers::NONE) => Self::ShowLog,
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => Self::InsertChar(c),
+            (KeyCode::Tab, KeyModifiers::NONE | KeyModifiers::SHIFT) => Self::InsertChar('\t'),
+            (KeyCode::Enter, KeyModifiers::NONE) => Self::InsertNewline,
+            (KeyCode::Backspace, KeyModifiers::NONE) => Self::DeleteBackward,
+            (KeyCode::Delete, KeyModifiers::NONE) => Self::DeleteForward,
+            (code, KeyModifiers::NONE) => Self::Move(Self::motion(code)?),
+            (code, KeyModifiers::CONTROL) => Self::Move(Self::control_motion(code)?),
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    /// 命令是否会修改文件，只读模式下不允许执行
+    pub fn is_edit(&self) -> bool {
+        matches!(
+            self,
+            Self::Save
+                | Self::InsertChar(_)
+                | Self::InsertNewline
+                | Self::DeleteBackward
+                | Self::DeleteForward
+        )
+    }
+
+    // 方向键对应的光标移动方式
+    fn motion(code: KeyCode) -> Option<Motion> {
+        let motion = match code {
+            KeyCode::Up => Motion::Up,
+            KeyCode::Down => Motion::Down,
+            KeyCode::Left => Motion::Left,
+            KeyCode::Right => Motion::Right,
+            KeyCode::Home => Motion::Home,
+            KeyCode::End => Motion::End,
+            KeyCode::PageUp => Motion::PageUp,
+            KeyCode::PageDown => Motion::PageDown,
+            _ => return None,
+        };
+        Some(motion)
+    }
+
+    // 按住 Ctrl 时方向键对应的光标移动方式
+    fn control_motion(code: KeyCode) -> Option<Motion> {
+        let motion = match code {
+            KeyCode::Left => Motion::WordBackward,
+            KeyCode::Right => Motion::WordForward,
+            KeyCode::Up => Motion::ParagraphBackward,
+            KeyCode::Down => Motion::ParagraphForward,
+            KeyCode::Home => Motion::BufferStart,
+            KeyCode::End => Motion::BufferEnd,
+            _ => return None,
+        };
+        Some(motion)
+    }
+}
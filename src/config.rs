@@ -0,0 +1,102 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 编辑器配置。
+///
+/// 配置文件位于 `$XDG_CONFIG_HOME/z-editor/config`，未设置时为 `~/.config/z-editor/config`。
+/// 每行一项 `键 = 值`，以 `#` 开头的行为注释，例如：
+///
+/// ```text
+/// # 终端不支持备用屏幕时关闭
+/// alternate_screen = false
+/// ```
+pub struct Config {
+    // 是否使用终端的备用屏幕
+    alternate_screen: bool,
+    // 终端支持时是否使用同步更新输出每一帧
+    synchronized_output: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            alternate_screen: true,
+            synchronized_output: true,
+        }
+    }
+}
+
+impl Config {
+    /// 读取默认位置的配置文件，文件不存在或无法读取时使用默认配置
+    pub fn load() -> Self {
+        let path = match Self::default_path() {
+            Some(path) => path,
+            None => return Self::default(),
+        };
+        match Self::from_file(&path) {
+            Ok(config) => config,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                warn!("读取配置文件失败：{:?}，{}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// 读取指定的配置文件，无法识别的配置项会被忽略
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        info!("读取配置文件：{:?}", path);
+        let mut config = Self::default();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            match line.split_once('=') {
+                Some((key, value)) => config.set(key.trim(), value.trim()),
+                None => warn!("配置文件第 {} 行格式错误：{}", number + 1, line),
+            }
+        }
+        Ok(config)
+    }
+
+    /// 是否使用终端的备用屏幕
+    pub fn alternate_screen(&self) -> bool {
+        self.alternate_screen
+    }
+
+    /// 终端支持时是否使用同步更新输出每一帧
+    pub fn synchronized_output(&self) -> bool {
+        self.synchronized_output
+    }
+
+    // 设置配置项
+    fn set(&mut self, key: &str, value: &str) {
+        match (key, Self::parse_bool(value)) {
+            ("alternate_screen", Some(value)) => self.alternate_screen = value,
+            ("synchronized_output", Some(value)) => self.synchronized_output = value,
+            _ => warn!("无法识别的配置项：{} = {}", key, value),
+        }
+    }
+
+    // 解析布尔值
+    fn parse_bool(value: &str) -> Option<bool> {
+        match value.to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" => Some(true),
+            "false" | "no" | "off" => Some(false),
+            _ => None,
+        }
+    }
+
+    // 默认配置文件路径
+    fn default_path() -> Option<PathBuf> {
+        let dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+        Some(dir.join("z-editor").join("config"))
+    }
+}
@@ -1,23 +1,35 @@
 use std::cmp;
-use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 
-use crossterm::event::KeyCode;
+use unicode_segmentation::UnicodeSegmentation;
 
-use crate::view::EditRow;
-use crate::{EditorView, TAB_SIZE};
+use crate::buffer::{EditResult, ReadOnlyError, TextBuffer};
+use crate::command::Motion;
+use crate::motion::Position;
+use crate::unicode::grapheme_width;
+use crate::EditorView;
 
-/// 光标控制器
+/// 光标控制器。
+///
+/// 行内与跨行的光标移动由 [`TextBuffer`] 计算，光标控制器记录光标位置与滚动偏移量；
+/// 软换行、翻页与滚动依赖窗口大小，需要同时传入 [`EditorView`]
 pub struct CursorController {
     // 行偏移量
     rows_offset: usize,
     // 列偏移量
     columns_offset: usize,
+    // 软换行模式下，首个可见行的分段偏移量
+    segment_offset: usize,
     // 原内容光标位置
     raw_position: Cursor,
     // 渲染内容光标位置
     render_position: Cursor,
-    // todo 考虑将 EditView 的只读借用加入结构体中
+}
+
+impl Default for CursorController {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl CursorController {
@@ -26,14 +38,20 @@ impl CursorController {
         Self {
             rows_offset: 0,
             columns_offset: 0,
+            segment_offset: 0,
             raw_position: Cursor(0, 0),
             render_position: Cursor(0, 0),
         }
     }
 
-    /// 获取当前光标位置元组，索引 0 为列，索引 1 为行
-    pub fn get_cursor(&mut self) -> &mut Cursor {
-        &mut self.render_position
+    /// 获取光标的渲染位置，索引 0 为渲染列，索引 1 为行。光标只能通过移动与编辑方法改变
+    pub fn get_cursor(&self) -> &Cursor {
+        &self.render_position
+    }
+
+    /// 获取光标在文本缓冲区中的位置
+    pub fn position(&self) -> Position {
+        Position::new(self.raw_position.1, self.raw_position.0)
     }
 
     /// 获取当前行偏移量
@@ -41,133 +59,414 @@ impl CursorController {
         self.rows_offset
     }
 
+    /// 获取列偏移量
     pub fn get_columns_offset(&self) -> usize {
         self.columns_offset
     }
 
+    /// 获取软换行模式下首个可见行的分段偏移量
+    pub fn get_segment_offset(&self) -> usize {
+        self.segment_offset
+    }
+
+    /// 获取光标在文本区域内的屏幕位置，返回 (列, 行)，不包含行号栏宽度
+    pub fn screen_position(&self, buffer: &TextBuffer, view: &EditorView) -> (usize, usize) {
+        if !view.is_soft_wrap() {
+            return (
+                self.render_position.0 - self.columns_offset,
+                self.render_position.1 - self.rows_offset,
+            );
+        }
+        let segments = view.wrap_segments(buffer, self.render_position.1);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        let y = self.visual_distance(buffer, view, index, usize::MAX);
+        (self.render_position.0 - segments[index].0, y)
+    }
+
     /// 屏幕滚动
-    pub fn scroll(&mut self, ecd: &EditorView) {
-        let win_size = ecd.get_win_size();
+    pub fn scroll(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let text_columns = view.get_text_columns(buffer);
         // 设置渲染列偏移量
-        self.render_position.0 = if self.render_position.1 < ecd.number_of_rows() {
-            self.calculate_render_x(ecd.get_edit_row(self.render_position.1))
-        } else {
-            0
-        };
+        self.render_position.0 = buffer.render_x(self.position());
+
+        if view.is_soft_wrap() {
+            self.scroll_wrapped(buffer, view);
+            return;
+        }
+        self.segment_offset = 0;
+        let offsets = (self.rows_offset, self.columns_offset);
 
         // 设置行偏移量
-        self.rows_offset = if self.render_position.1 >= self.rows_offset + ecd.get_win_max_rows() {
-            self.render_position.1 - ecd.get_win_max_rows() + 1
+        self.rows_offset = if self.render_position.1 >= self.rows_offset + view.get_win_max_rows() {
+            self.render_position.1 - view.get_win_max_rows() + 1
         } else {
             cmp::min(self.rows_offset, self.render_position.1)
         };
 
-        // 设置列偏移量
-        self.columns_offset = if self.render_position.0 >= self.columns_offset + win_size.0 {
-            self.render_position.0 - win_size.0 + 1
-        } else {
-            cmp::min(self.columns_offset, self.render_position.0)
-        };
-        trace!(
-            "屏幕发生滚动，渲染列偏移量：{}，行偏移量：{}，列偏移量：{}",
-            self.render_position.0,
-            self.rows_offset,
-            self.columns_offset
-        );
+        // 设置列偏移量，需扣除行号栏所占宽度，并保证光标处的宽字符完整显示
+        let cursor_width = self.cursor_grapheme_width(buffer);
+        self.columns_offset =
+            if self.render_position.0 + cursor_width > self.columns_offset + text_columns {
+                (self.render_position.0 + cursor_width).saturating_sub(text_columns)
+            } else {
+                cmp::min(self.columns_offset, self.render_position.0)
+            };
+        // 仅在偏移量变化时记录，避免每一帧都写入日志
+        if offsets != (self.rows_offset, self.columns_offset) {
+            trace!(
+                "屏幕发生滚动，渲染列偏移量：{}，行偏移量：{}，列偏移量：{}",
+                self.render_position.0,
+                self.rows_offset,
+                self.columns_offset
+            );
+        }
     }
 
-    /// 移动光标
-    pub fn move_cursor(&mut self, direction: KeyCode, ecd: &EditorView) {
-        let number_of_rows = ecd.number_of_rows();
-        debug!("光标变动前位置：{}", self.render_position);
-        match direction {
-            KeyCode::Up => {
-                self.move_up();
-            }
-            KeyCode::Left => {
-                self.move_left(ecd);
-            }
-            KeyCode::Right => {
-                self.move_right(ecd);
+    // 获取光标所在字形簇的显示宽度，光标位于行末时视为一列
+    fn cursor_grapheme_width(&self, buffer: &TextBuffer) -> usize {
+        if self.raw_position.1 >= buffer.number_of_rows() {
+            return 1;
+        }
+        let raw_content = buffer.row(self.raw_position.1);
+        raw_content[raw_content.floor_char_boundary(self.raw_position.0)..]
+            .graphemes(true)
+            .next()
+            .map_or(1, |g| grapheme_width(g).max(1))
+    }
+
+    // 软换行模式下按屏幕行滚动，不存在水平滚动
+    fn scroll_wrapped(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let max_rows = view.get_win_max_rows().max(1);
+        let segments = view.wrap_segments(buffer, self.render_position.1);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        let top = (self.rows_offset, self.segment_offset);
+        let offsets = (self.rows_offset, self.segment_offset, self.columns_offset);
+        self.columns_offset = 0;
+        if (self.render_position.1, index) < top {
+            // 光标位于可见区域上方
+            self.rows_offset = self.render_position.1;
+            self.segment_offset = index;
+        } else if self.visual_distance(buffer, view, index, max_rows) >= max_rows {
+            // 光标位于可见区域下方，从光标处向上回溯一屏
+            (self.rows_offset, self.segment_offset) = Self::visual_rows_above(
+                buffer,
+                view,
+                (self.render_position.1, index),
+                max_rows - 1,
+            );
+        }
+        if offsets != (self.rows_offset, self.segment_offset, self.columns_offset) {
+            trace!(
+                "软换行屏幕发生滚动，行偏移量：{}，分段偏移量：{}",
+                self.rows_offset,
+                self.segment_offset
+            );
+        }
+    }
+
+    // 计算首个可见屏幕行到光标所在屏幕行的距离，超过 limit 时提前返回
+    fn visual_distance(
+        &self,
+        buffer: &TextBuffer,
+        view: &EditorView,
+        index: usize,
+        limit: usize,
+    ) -> usize {
+        let mut distance = index;
+        for row in self.rows_offset..self.render_position.1 {
+            distance += view.wrap_segments(buffer, row).len();
+            if distance >= limit.saturating_add(self.segment_offset) {
+                break;
             }
-            KeyCode::Down => {
-                self.move_down(ecd);
+        }
+        distance.saturating_sub(self.segment_offset)
+    }
+
+    // 软换行模式下从指定的 (行, 分段) 向上回溯 count 个屏幕行，到达文件开头时停止
+    fn visual_rows_above(
+        buffer: &TextBuffer,
+        view: &EditorView,
+        (mut row, mut segment): (usize, usize),
+        count: usize,
+    ) -> (usize, usize) {
+        for _ in 0..count {
+            if segment > 0 {
+                segment -= 1;
+            } else if row > 0 {
+                row -= 1;
+                segment = view.wrap_segments(buffer, row).len() - 1;
+            } else {
+                break;
             }
-            KeyCode::Home => {
-                self.move_home();
+        }
+        (row, segment)
+    }
+
+    /// 滚动屏幕使光标所在行位于可见区域中央
+    pub fn center_cursor(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let half = view.get_win_max_rows() / 2;
+        if !view.is_soft_wrap() {
+            self.rows_offset = self.render_position.1.saturating_sub(half);
+            return;
+        }
+        let segments = view.wrap_segments(buffer, self.render_position.1);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        (self.rows_offset, self.segment_offset) =
+            Self::visual_rows_above(buffer, view, (self.render_position.1, index), half);
+    }
+
+    // 查找渲染列所在的分段下标，非末尾分段的结束位置属于下一个分段
+    fn segment_index(segments: &[(usize, usize)], render_x: usize) -> usize {
+        segments
+            .iter()
+            .rposition(|&(start, _)| start <= render_x)
+            .unwrap_or(0)
+    }
+
+    /// 移动光标
+    pub fn move_cursor(&mut self, motion: Motion, buffer: &TextBuffer, view: &EditorView) {
+        debug!("光标变动前位置：{}", self.render_position);
+        match motion {
+            Motion::PageUp => return self.move_page_up(buffer, view),
+            Motion::PageDown => return self.move_page_down(buffer, view),
+            Motion::WordForward => return self.move_word_forward(buffer),
+            Motion::WordBackward => return self.move_word_backward(buffer),
+            Motion::ParagraphForward => return self.move_paragraph_forward(buffer),
+            Motion::ParagraphBackward => return self.move_paragraph_backward(buffer),
+            Motion::BufferStart => return self.set_position(buffer, buffer.buffer_start()),
+            Motion::BufferEnd => return self.move_buffer_end(buffer),
+            Motion::MatchingBracket => {
+                self.move_to_matching_bracket(buffer);
+                return;
             }
-            KeyCode::End => {
-                self.move_end(ecd);
+            _ => {}
+        }
+        if view.is_soft_wrap() {
+            match motion {
+                Motion::Up => return self.move_visual_up(buffer, view),
+                Motion::Down => return self.move_visual_down(buffer, view),
+                Motion::Home => return self.move_segment_home(buffer, view),
+                Motion::End => return self.move_segment_end(buffer, view),
+                _ => {}
             }
-            _ => unimplemented!(),
         }
+        match motion {
+            Motion::Up => self.move_up(buffer),
+            Motion::Left => self.move_left(buffer),
+            Motion::Right => self.move_right(buffer),
+            Motion::Down => self.move_down(buffer),
+            Motion::Home => self.move_home(),
+            Motion::End => self.move_end(buffer),
+            _ => {}
+        }
+        debug!("光标变动后位置：{}", self.render_position);
+    }
 
-        // 光标上下移动时，重新设置列偏移量
-        let row_len = if self.render_position.1 < number_of_rows {
-            ecd.rendered_content_of_row(self.render_position.1).len()
+    /// 光标上移，保持渲染列不变
+    pub fn move_up(&mut self, buffer: &TextBuffer) {
+        let row = self.render_position.1.saturating_sub(1);
+        self.move_to(buffer, row, self.render_position.0);
+    }
+
+    /// 光标下移，保持渲染列不变，最多移动至文本末尾之后的行
+    pub fn move_down(&mut self, buffer: &TextBuffer) {
+        let row = self.render_position.1 + 1;
+        if row <= buffer.number_of_rows() {
+            self.move_to(buffer, row, self.render_position.0);
+        }
+    }
+
+    /// 软换行模式下光标上移一个屏幕行
+    pub fn move_visual_up(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let row = self.render_position.1;
+        let segments = view.wrap_segments(buffer, row);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        let column = self.render_position.0 - segments[index].0;
+        if index > 0 {
+            self.set_visual_position(buffer, view, (row, index - 1), column);
+        } else if row > 0 {
+            let last = view.wrap_segments(buffer, row - 1).len() - 1;
+            self.set_visual_position(buffer, view, (row - 1, last), column);
+        }
+    }
+
+    /// 软换行模式下光标下移一个屏幕行
+    pub fn move_visual_down(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let row = self.render_position.1;
+        let segments = view.wrap_segments(buffer, row);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        let column = self.render_position.0 - segments[index].0;
+        if index + 1 < segments.len() {
+            self.set_visual_position(buffer, view, (row, index + 1), column);
+        } else if row < buffer.number_of_rows() {
+            self.set_visual_position(buffer, view, (row + 1, 0), column);
+        }
+    }
+
+    /// 软换行模式下光标移动至当前分段开头，已在分段开头时移动至行首
+    pub fn move_segment_home(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let segments = view.wrap_segments(buffer, self.render_position.1);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        if self.render_position.0 == segments[index].0 {
+            self.move_home();
+        } else {
+            self.set_visual_position(buffer, view, (self.render_position.1, index), 0);
+        }
+    }
+
+    /// 软换行模式下光标移动至当前分段末尾，已在分段末尾时移动至行末
+    pub fn move_segment_end(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        let row = self.render_position.1;
+        if row >= buffer.number_of_rows() {
+            return;
+        }
+        let segments = view.wrap_segments(buffer, row);
+        let index = Self::segment_index(&segments, self.render_position.0);
+        let (start, end) = segments[index];
+        if index + 1 == segments.len() || self.render_position.0 + 1 == end {
+            self.move_end(buffer);
+        } else {
+            self.set_visual_position(buffer, view, (row, index), end - start);
+        }
+    }
+
+    // 将光标移动到指定行的指定分段中，列超出分段时停留在分段末尾
+    fn set_visual_position(
+        &mut self,
+        buffer: &TextBuffer,
+        view: &EditorView,
+        (row, index): (usize, usize),
+        column: usize,
+    ) {
+        let segments = view.wrap_segments(buffer, row);
+        let (start, end) = segments[index];
+        // 非末尾分段的结束位置属于下一个分段，光标最多停留在其前一列
+        let max_x = if index + 1 < segments.len() {
+            end - 1
         } else {
-            0
+            end
         };
-        self.render_position.0 = cmp::min(self.render_position.0, row_len);
-        debug!("光标变动后位置：{}", self.render_position);
+        self.move_to(buffer, row, cmp::min(start + column, max_x));
+    }
+
+    /// 将光标移动到指定行的指定渲染列，超出文本范围时停留在文本末尾或行末。
+    ///
+    /// 渲染列位于宽字符或制表符中间时，原内容位置取其起始位置，渲染列保持不变
+    pub fn move_to(&mut self, buffer: &TextBuffer, row: usize, render_x: usize) {
+        let position = buffer.position_at_render_x(row, render_x);
+        let render_x = cmp::min(render_x, buffer.row_width(position.row));
+        self.raw_position = Cursor(position.x, position.row);
+        self.render_position = Cursor(render_x, position.row);
     }
 
-    /// 光标上移
-    pub fn move_up(&mut self) {
-        self.render_position.1 = self.render_position.1.saturating_sub(1);
-        self.raw_position.1 = self.raw_position.1.saturating_sub(1);
+    /// 将光标移动到指定行的第 `column` 个字符处，列位于字形簇中间时取字形簇起始位置，超出行末时停留在行末
+    pub fn move_to_char(&mut self, buffer: &TextBuffer, row: usize, column: usize) {
+        self.set_position(buffer, buffer.position_at_char(row, column));
     }
 
-    /// 光标下移
-    pub fn move_down(&mut self, ecd: &EditorView) {
-        if self.render_position.1 < ecd.number_of_rows() {
-            self.render_position.1 += 1;
-            self.raw_position.1 += 1;
+    /// 将光标移动到文本缓冲区中的指定位置，并据此计算渲染列
+    pub fn set_position(&mut self, buffer: &TextBuffer, position: Position) {
+        self.raw_position = Cursor(position.x, position.row);
+        self.render_position = Cursor(buffer.render_x(position), position.row);
+    }
+
+    /// 光标移动至下一个单词开头，位于行末时移动至下一行行首。
+    ///
+    /// 按 Unicode 单词边界划分，中日韩文字逐字停留，标点单独视为一个单词
+    pub fn move_word_forward(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.word_forward(self.position()));
+    }
+
+    /// 光标移动至当前或上一个单词开头，位于行首时移动至上一行行末
+    pub fn move_word_backward(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.word_backward(self.position()));
+    }
+
+    /// 光标移动至下一个段落之后的空白行，之后没有空白行时移动至文件末尾
+    pub fn move_paragraph_forward(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.paragraph_forward(self.position()));
+    }
+
+    /// 光标移动至上一个段落之前的空白行，之前没有空白行时移动至文件开头
+    pub fn move_paragraph_backward(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.paragraph_backward(self.position()));
+    }
+
+    /// 光标移动至文件末尾
+    pub fn move_buffer_end(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.buffer_end());
+    }
+
+    /// 光标移动至光标处括号的匹配括号，光标处不是括号或找不到匹配括号时不移动并返回 false
+    pub fn move_to_matching_bracket(&mut self, buffer: &TextBuffer) -> bool {
+        match buffer.matching_bracket(self.position()) {
+            Some(position) => {
+                self.set_position(buffer, position);
+                true
+            }
+            None => false,
         }
     }
 
-    /// 光标左移
-    pub fn move_left(&mut self, ecd: &EditorView) {
-        if self.render_position.0 > 0 {
-            self.render_position.0 -= 1;
-            let raw_content = ecd.raw_content_of_row(self.raw_position.1);
-            self.raw_position.0 = (0..self.raw_position.0)
-                .rposition(|i| raw_content.is_char_boundary(i))
-                .unwrap();
+    /// 光标左移一个字形簇，行首左移切换到上一行行末，文件开头不移动
+    pub fn move_left(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.prev_grapheme(self.position()));
+    }
+
+    /// 光标右移一个字形簇，行末右移切换到下一行行首
+    pub fn move_right(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.next_grapheme(self.position()));
+    }
+
+    /// 在光标处插入字符，光标移动至插入字符之后
+    pub fn insert_char(&mut self, buffer: &mut TextBuffer, c: char) -> EditResult {
+        let mut buf = [0; 4];
+        let text = c.encode_utf8(&mut buf);
+        let Position { row, x } = self.position();
+        buffer.insert(row, x, text)?;
+        self.set_position(buffer, Position::new(row, x + text.len()));
+        Ok(())
+    }
+
+    /// 在光标处插入换行，光标移动至新行行首
+    pub fn insert_newline(&mut self, buffer: &mut TextBuffer) -> EditResult {
+        let Position { row, x } = self.position();
+        buffer.split_row(row, x)?;
+        self.set_position(buffer, Position::new(row + 1, 0));
+        Ok(())
+    }
+
+    /// 删除光标前的一个字形簇，光标位于行首时与上一行合并。返回内容是否发生变化
+    pub fn delete_backward(&mut self, buffer: &mut TextBuffer) -> Result<bool, ReadOnlyError> {
+        let position = self.position();
+        if position.row >= buffer.number_of_rows() {
+            return Ok(false);
+        }
+        let start = buffer.prev_grapheme(position);
+        if start == position {
+            return Ok(false);
+        }
+        if start.row == position.row {
+            buffer.remove(position.row, start.x..position.x)?;
         } else {
-            // 文首左移切换到上一行文末
-            // todo 考虑边界情况，例如首行文首左移
-            self.render_position.1 -= 1;
-            self.raw_position.1 -= 1;
-            self.render_position.0 = ecd.rendered_content_of_row(self.render_position.1).len();
-            self.raw_position.0 = ecd.raw_content_of_row(self.raw_position.1).len();
-        }
-    }
-
-    /// 光标右移
-    pub fn move_right(&mut self, ecd: &EditorView) {
-        if self.render_position.1 < ecd.number_of_rows() {
-            match self
-                .render_position
-                .0
-                .cmp(&ecd.rendered_content_of_row(self.render_position.1).len())
-            {
-                Ordering::Less => {
-                    self.render_position.0 += 1;
-                    let raw_text = ecd.raw_content_of_row(self.raw_position.1);
-                    self.raw_position.0 += ((self.raw_position.0 + 1)..(self.raw_position.0 + 3))
-                        .position(|i| raw_text.is_char_boundary(i))
-                        .unwrap()
-                        + 1;
-                }
-                _ => {
-                    self.render_position.1 += 1;
-                    self.raw_position.1 += 1;
-                    self.render_position.0 = 0;
-                    self.raw_position.0 = 0;
-                }
-            }
+            buffer.join_with_next_row(start.row)?;
+        }
+        self.set_position(buffer, start);
+        Ok(true)
+    }
+
+    /// 删除光标处的一个字形簇，光标位于行末时与下一行合并。返回内容是否发生变化
+    pub fn delete_forward(&mut self, buffer: &mut TextBuffer) -> Result<bool, ReadOnlyError> {
+        let position = self.position();
+        let end = buffer.next_grapheme(position);
+        if end.row == position.row && end.x > position.x {
+            buffer.remove(position.row, position.x..end.x)?;
+        } else if end.row < buffer.number_of_rows() {
+            buffer.join_with_next_row(position.row)?;
+        } else {
+            return Ok(false);
         }
+        Ok(true)
     }
 
     /// 光标移动至行首
@@ -176,41 +475,33 @@ impl CursorController {
         self.raw_position.0 = 0;
     }
 
-    /// 光标移动至行末
-    pub fn move_end(&mut self, ecd: &EditorView) {
-        self.render_position.0 = ecd.rendered_content_of_row(self.render_position.1).len();
-        self.raw_position.0 = ecd.raw_content_of_row(self.raw_position.1).len();
+    /// 光标移动至行末，位于最后一行之后时不动
+    pub fn move_end(&mut self, buffer: &TextBuffer) {
+        self.set_position(buffer, buffer.line_end(self.position()));
     }
 
     /// 光标上翻页
-    pub fn move_page_up(&mut self, ecd: &EditorView) {
-        self.render_position.1 = self.rows_offset;
-        (0..ecd.get_win_max_rows()).for_each(|_| {
-            self.move_up();
-        })
+    pub fn move_page_up(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        if view.is_soft_wrap() {
+            (0..view.get_win_max_rows()).for_each(|_| self.move_visual_up(buffer, view));
+            return;
+        }
+        let row = self.rows_offset.saturating_sub(view.get_win_max_rows());
+        self.move_to(buffer, row, self.render_position.0);
     }
 
     /// 光标下翻页
-    pub fn move_page_down(&mut self, ecd: &EditorView) {
-        self.render_position.1 = cmp::min(
-            self.rows_offset + ecd.get_win_max_rows() + 1,
-            ecd.number_of_rows(),
+    pub fn move_page_down(&mut self, buffer: &TextBuffer, view: &EditorView) {
+        if view.is_soft_wrap() {
+            (0..view.get_win_max_rows()).for_each(|_| self.move_visual_down(buffer, view));
+            return;
+        }
+        let row = cmp::min(
+            self.rows_offset + view.get_win_max_rows() + 1,
+            buffer.number_of_rows(),
         );
-        (0..ecd.get_win_max_rows()).for_each(|_| {
-            self.move_down(ecd);
-        })
-    }
-
-    fn calculate_render_x(&self, row: &EditRow) -> usize {
-        row.get_raw_content()[..self.raw_position.0]
-            .chars()
-            .fold(0, |render_x, c| {
-                if c == '\t' {
-                    render_x + ((TAB_SIZE - 1) - (render_x % TAB_SIZE) + 1)
-                } else {
-                    render_x + 1
-                }
-            })
+        let row = cmp::min(row + view.get_win_max_rows(), buffer.number_of_rows());
+        self.move_to(buffer, row, self.render_position.0);
     }
 }
 
@@ -224,18 +515,10 @@ impl Cursor {
         self.0
     }
 
-    pub fn set_x(&mut self, new_x: usize) {
-        self.0 = new_x;
-    }
-
     /// 获取光标 y 轴偏移量
     pub fn get_y(&self) -> usize {
         self.1
     }
-
-    pub fn set_y(&mut self, new_y: usize) {
-        self.1 = new_y;
-    }
 }
 
 impl Display for Cursor {
@@ -1,7 +0,0 @@
-pub struct EditLog {}
-
-impl EditLog {
-    pub fn new() -> Self {
-        Self {}
-    }
-}
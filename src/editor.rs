@@ -1,87 +1,898 @@
-use std::path::Path;
-use std::time::Duration;
-use std::{cmp, env, fs};
+use std::fs;
+use std::fs::{Metadata, OpenOptions};
+use std::io;
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
-use crossterm::{event, terminal};
+use crossterm::event;
+use crossterm::event::{Event, KeyEvent};
 
-use crate::edit_log::EditLog;
-use crate::status::StatusInfo;
+use crate::backend::{Backend, CrosstermBackend};
+use crate::buffer::{LineEnding, ReadOnlyError, TextBuffer};
+use crate::cli::{FileArg, Options};
+use crate::command::{Command, Motion};
+use crate::config::Config;
+use crate::encoding::FileEncoding;
+use crate::error::{EditorError, Result};
+use crate::file_stamp::FileStamp;
+use crate::hex_view::{self, HexView};
+use crate::logger;
+use crate::mapped_file::MappedFile;
+use crate::prompt::{Prompt, PromptKind, PromptResult};
+use crate::status::{Status, StatusInfo};
+use crate::swap::{SwapFile, SwapStatus};
 use crate::view::EditorView;
-use crate::CursorController;
+use crate::{terminal_guard, CursorController};
+
+/// 超过该大小的文件以内存映射方式只读打开。更小的文件读入内存，可以编辑。
+/// 读入内存的文件需要解码并建立 Rope，数百 MB 的日志已会明显拖慢启动
+const MMAP_THRESHOLD: u64 = 256 * 1024 * 1024;
+/// 内容发生变化后写入交换文件的最短间隔
+const SWAP_INTERVAL: Duration = Duration::from_secs(4);
+/// 启动时消息栏中显示的帮助信息
+const HELP_MESSAGE: &str = "HELP: Ctrl-S = Save | Ctrl-Q = Quit | Ctrl-Z = Suspend | Ctrl-G = Go to line | Ctrl-N = Toggle relative line numbers | Alt-Z = Toggle soft wrap | Alt-R/Alt-E = Reopen/Convert encoding | Alt-L = Convert line endings | Alt-M = Matching bracket | F12 = Show log.";
+
+/// 读取到的文件内容
+enum FileContent {
+    /// 以内存映射方式打开的超大文件
+    Mapped(MappedFile),
+    /// 文件元数据与全部内容
+    Bytes(Metadata, Vec<u8>),
+}
 
 /// 编辑器
 pub struct Editor {
+    // 文本缓冲区
+    buffer: TextBuffer,
     // 编辑视图
     editor_view: EditorView,
     // 光标控制器
     cursor_controller: CursorController,
     // 状态信息
     status_info: StatusInfo,
-    // 编辑日志
-    edit_log: EditLog,
+    // 十六进制视图，打开二进制文件时使用
+    hex_view: Option<HexView>,
+    // 内容中是否存在无法以当前编码解码的字节，存在时须先转换编码才能保存，避免以替换字符覆盖原内容
+    undecodable: bool,
+    // 消息栏中正在进行的提示输入
+    prompt: Option<Prompt>,
+    // 交换文件，只读模式、其他进程正在编辑或保留了过期交换文件时为 None
+    swap_file: Option<SwapFile>,
+    // 上次写入交换文件后内容是否发生变化
+    swap_pending: bool,
+    // 上次写入交换文件的时间
+    swap_written_at: Instant,
+    // 上次登记应急副本后内容是否发生变化
+    emergency_pending: bool,
+    // 打开或保存文件时的文件快照，用于检测文件是否在外部被修改
+    file_stamp: Option<FileStamp>,
+    // 已提示过的外部修改后的文件快照，用户选择保留当前内容后才替换 file_stamp
+    external_stamp: Option<FileStamp>,
+    // 命令行中指定的文件
+    files: Vec<FileArg>,
+    // 当前打开的文件在 files 中的索引
+    file_index: usize,
+    // 是否以只读模式打开所有文件
+    read_only: bool,
+    // 已读取的标准输入内容
+    stdin: Option<Vec<u8>>,
+    // 打开日志查看器前的光标位置 (行, 渲染列)，未在查看日志时为 None
+    log_viewer: Option<(usize, usize)>,
 }
 
 impl Editor {
-    // 创建空内容的编辑器
-    fn empty(initial_message: String) -> Self {
+    // 根据编辑视图创建空内容的编辑器
+    fn build(editor_view: EditorView, files: Vec<FileArg>, read_only: bool) -> Self {
         Self {
-            editor_view: EditorView::new(Vec::new()),
+            buffer: TextBuffer::new(),
+            editor_view,
             cursor_controller: CursorController::new(),
-            status_info: StatusInfo::new(None, 0, initial_message),
-            edit_log: EditLog::new(),
+            status_info: StatusInfo::new(None, String::new()),
+            hex_view: None,
+            undecodable: false,
+            prompt: None,
+            swap_file: None,
+            swap_pending: false,
+            swap_written_at: Instant::now(),
+            emergency_pending: false,
+            file_stamp: None,
+            external_stamp: None,
+            files,
+            file_index: 0,
+            read_only,
+            stdin: None,
+            log_viewer: None,
         }
     }
 
-    // 根据指定文件创建编辑器
-    fn from_file(file: &Path, initial_message: String) -> Self {
-        let content: Vec<String> = fs::read_to_string(file)
-            .unwrap()
-            .lines()
-            .map(|it| String::from(it))
-            .collect();
-        let lines = content.len();
-        info!("读取文件：{:?}，总行数：{}", file, lines);
-        Self {
-            editor_view: EditorView::new(content),
-            cursor_controller: CursorController::new(),
-            status_info: StatusInfo::new(Some(file.to_path_buf()), lines, initial_message),
-            edit_log: EditLog::new(),
+    // 打开指定文件并移动到指定位置，未指定文件时打开空缓冲区
+    fn load(&mut self, file: Option<&FileArg>, initial_message: String) -> Result<()> {
+        self.reset_file_state();
+        let file = match file {
+            Some(file) => file,
+            None => {
+                self.buffer = TextBuffer::new();
+                self.status_info = StatusInfo::new(None, initial_message);
+                return Ok(());
+            }
+        };
+        match file.path() {
+            Some(path) => match Self::read_file(path) {
+                Ok(content) => self.load_content(path, content, initial_message),
+                Err(e) => self.load_unreadable_file(path, e),
+            },
+            None => {
+                let bytes = self.read_stdin()?;
+                self.load_bytes(None, bytes, initial_message);
+                self.status_info.set_default_name("[stdin]");
+            }
+        }
+        if self.read_only {
+            self.status_info.set_status(Status::ReadOnly);
+        }
+        if let (Some(line), None) = (file.line(), &self.hex_view) {
+            let column = file.column().unwrap_or(1);
+            self.cursor_controller
+                .move_to_char(&self.buffer, line - 1, column - 1);
+        }
+        Ok(())
+    }
+
+    // 清除与当前打开的内容相关的状态
+    fn reset_file_state(&mut self) {
+        self.cursor_controller = CursorController::new();
+        self.hex_view = None;
+        self.undecodable = false;
+        self.prompt = None;
+        self.file_stamp = None;
+        self.external_stamp = None;
+        self.swap_file = None;
+        self.swap_pending = false;
+        self.log_viewer = None;
+    }
+
+    // 读取文件，超大文件以内存映射方式打开
+    fn read_file(file: &Path) -> io::Result<FileContent> {
+        let metadata = fs::metadata(file)?;
+        if metadata.len() >= MMAP_THRESHOLD {
+            return MappedFile::open(file).map(FileContent::Mapped);
         }
+        let bytes = fs::read(file)?;
+        Ok(FileContent::Bytes(metadata, bytes))
     }
 
-    /// 创建编辑器
-    pub fn new() -> Self {
-        terminal::enable_raw_mode().unwrap();
+    // 读取标准输入的全部内容，切换文件时使用已读取的内容
+    fn read_stdin(&mut self) -> Result<Vec<u8>> {
+        if let Some(bytes) = &self.stdin {
+            return Ok(bytes.clone());
+        }
+        let mut bytes = Vec::new();
+        io::stdin()
+            .lock()
+            .read_to_end(&mut bytes)
+            .map_err(|e| EditorError::file("标准输入", e))?;
+        info!("读取标准输入，字节数：{}", bytes.len());
+        self.stdin = Some(bytes.clone());
+        Ok(bytes)
+    }
 
-        let mut arg = env::args();
-        let initial_message = "HELP: Ctrl-Q = Quit.".into();
-        info!("启动编辑器，启动参数：{:?}", arg);
+    // 根据文件大小与内容选择打开方式
+    fn load_content(&mut self, file: &Path, content: FileContent, initial_message: String) {
+        let (metadata, bytes) = match content {
+            FileContent::Mapped(mapped_file) => return self.load_mapped_file(file, mapped_file),
+            FileContent::Bytes(metadata, bytes) => (metadata, bytes),
+        };
+        let file_stamp = FileStamp::new(&metadata, &bytes);
+        self.load_bytes(Some(file), bytes, initial_message);
+        self.file_stamp = Some(file_stamp);
+        if !Self::is_writable(file) {
+            warn!("文件不可写入：{:?}", file);
+            self.status_info.set_status(Status::ReadOnly);
+            self.status_info
+                .set_message("没有写入权限，已以只读模式打开".to_string());
+        }
+    }
 
-        match arg.nth(1) {
-            None => Self::empty(initial_message),
-            Some(file) => Self::from_file(file.as_ref(), initial_message),
+    // 根据内容选择以文本方式或十六进制视图打开
+    fn load_bytes(&mut self, file: Option<&Path>, bytes: Vec<u8>, initial_message: String) {
+        if !FileEncoding::has_bom(&bytes) && hex_view::is_binary(&bytes) {
+            self.load_binary_file(file, bytes);
+        } else {
+            self.load_text_file(file, &bytes, initial_message);
         }
     }
 
+    // 以文本方式打开文件
+    fn load_text_file(&mut self, file: Option<&Path>, bytes: &[u8], initial_message: String) {
+        let encoding = FileEncoding::detect(bytes);
+        let (text, had_errors) = encoding.decode(bytes);
+        let buffer = TextBuffer::from_text(&text);
+        let lines = buffer.number_of_rows();
+        info!(
+            "读取文件：{:?}，编码：{}，总行数：{}",
+            file,
+            encoding.name(),
+            lines
+        );
+        let initial_message = if had_errors {
+            warn!("文件中存在无法以 {} 编码解码的字节", encoding.name());
+            Self::undecodable_message(&encoding)
+        } else if buffer.line_ending() == LineEnding::Mixed {
+            "警告：文件中混合使用了 LF 与 CRLF 换行符，可使用 Alt-L 统一转换".to_string()
+        } else {
+            initial_message
+        };
+        self.buffer = buffer;
+        self.undecodable = had_errors;
+        self.status_info = StatusInfo::new(file.map(Path::to_path_buf), initial_message);
+        self.status_info.set_encoding(encoding);
+    }
+
+    // 以内存映射方式只读打开超大文件
+    fn load_mapped_file(&mut self, file: &Path, mapped_file: MappedFile) {
+        info!("以只读模式映射文件：{:?}", file);
+        self.buffer = TextBuffer::from_mapped_file(mapped_file);
+        let message = "文件过大，已以只读模式打开。Ctrl-Q = Quit.".to_string();
+        self.status_info = StatusInfo::new(Some(file.to_path_buf()), message);
+        self.status_info.set_status(Status::ReadOnly);
+    }
+
+    // 以十六进制视图打开二进制文件
+    fn load_binary_file(&mut self, file: Option<&Path>, bytes: Vec<u8>) {
+        info!("检测到二进制文件：{:?}，字节数：{}", file, bytes.len());
+        let message =
+            "检测到二进制文件，已以十六进制视图打开。Ctrl-E = Toggle hex editing | Ctrl-S = Save."
+                .to_string();
+        self.buffer = TextBuffer::new();
+        self.status_info = StatusInfo::new(file.map(Path::to_path_buf), message);
+        self.hex_view = Some(HexView::new(bytes));
+    }
+
+    // 文件无法读取时打开空缓冲区：文件不存在时视为新文件，其他错误时以只读模式打开，避免保存时覆盖原文件
+    fn load_unreadable_file(&mut self, file: &Path, error: io::Error) {
+        self.buffer = TextBuffer::new();
+        self.status_info = if error.kind() == io::ErrorKind::NotFound {
+            info!("创建新文件：{:?}", file);
+            let message = format!("新文件：{}", file.display());
+            StatusInfo::new(Some(file.to_path_buf()), message)
+        } else {
+            warn!("无法读取文件：{:?}，{}", file, error);
+            let message = format!("无法读取文件：{}", EditorError::file(file, error));
+            let mut status_info = StatusInfo::new(Some(file.to_path_buf()), message);
+            status_info.set_status(Status::ReadOnly);
+            status_info
+        };
+    }
+
+    // 文件是否可以写入，以写入模式打开文件但不修改内容
+    fn is_writable(file: &Path) -> bool {
+        OpenOptions::new().write(true).open(file).is_ok()
+    }
+
+    /// 按命令行选项创建编辑器，终端在编辑器创建成功后才进入 Raw 模式
+    pub fn new(options: &Options) -> Result<Self> {
+        info!("启动编辑器，启动参数：{:?}", options);
+        let config = match options.config() {
+            Some(path) => Config::from_file(path).map_err(|e| EditorError::file(path, e))?,
+            None => Config::load(),
+        };
+        let backend = Box::new(CrosstermBackend::new(&config));
+        let mut files = options.files().to_vec();
+        // 未指定文件且内容通过管道输入时从标准输入读取
+        if files.is_empty() && !io::stdin().is_terminal() {
+            files.push(FileArg::new(None, None, None));
+        }
+        let mut editor = Self::open_files(files, options.read_only(), backend)?;
+        terminal_guard::reopen_tty().map_err(|e| EditorError::file("/dev/tty", e))?;
+        editor.check_swap_file();
+        editor.editor_view.enter_terminal()?;
+        Ok(editor)
+    }
+
+    /// 使用指定的后端创建编辑器并打开文件，未指定文件时打开空缓冲区。
+    ///
+    /// 不检查交换文件，也不进入编辑状态
+    pub fn open(file: Option<&Path>, backend: Box<dyn Backend>) -> Result<Self> {
+        let files = file
+            .map(|file| FileArg::new(Some(file.to_path_buf()), None, None))
+            .into_iter()
+            .collect();
+        Self::open_files(files, false, backend)
+    }
+
+    /// 使用指定的后端创建编辑器并打开第一个文件，可使用 Alt-N/Alt-P 切换至其他文件。
+    ///
+    /// 不检查交换文件，也不进入编辑状态
+    pub fn open_files(
+        files: Vec<FileArg>,
+        read_only: bool,
+        backend: Box<dyn Backend>,
+    ) -> Result<Self> {
+        let editor_view = EditorView::new(backend)?;
+        let mut editor = Self::build(editor_view, files, read_only);
+        let initial_message = if editor.files.len() > 1 {
+            format!(
+                "文件 1/{}。Alt-N/Alt-P = Next/Previous file | {}",
+                editor.files.len(),
+                HELP_MESSAGE
+            )
+        } else {
+            HELP_MESSAGE.to_string()
+        };
+        let first = editor.files.first().cloned();
+        editor.load(first.as_ref(), initial_message)?;
+        Ok(editor)
+    }
+
+    // 切换至下一个或上一个文件，存在未保存的修改时拒绝切换
+    fn switch_file(&mut self, forward: bool) -> Result<()> {
+        let count = self.files.len();
+        if count < 2 {
+            self.status_info.set_message("只打开了一个文件".to_string());
+            return Ok(());
+        }
+        if matches!(self.status_info.get_status(), Status::Modified) {
+            self.status_info
+                .set_message("存在未保存的修改，请先保存".to_string());
+            return Ok(());
+        }
+        self.remove_swap_file();
+        self.file_index = if forward {
+            (self.file_index + 1) % count
+        } else {
+            (self.file_index + count - 1) % count
+        };
+        let file = self.files[self.file_index].clone();
+        self.load(Some(&file), String::new())?;
+        if self.status_info.get_message().is_empty() {
+            self.status_info.set_message(format!(
+                "文件 {}/{}：{}",
+                self.file_index + 1,
+                count,
+                self.status_info.file_name_or_default()
+            ));
+        }
+        self.check_swap_file();
+        Ok(())
+    }
+
+    // 打开或关闭日志查看器，关闭后重新打开原来的内容并恢复光标位置。存在未保存的修改时拒绝打开
+    fn toggle_log_viewer(&mut self) -> Result<()> {
+        if let Some((row, x)) = self.log_viewer {
+            let file = self.files.get(self.file_index).cloned();
+            self.load(file.as_ref(), "已关闭日志查看器".to_string())?;
+            self.cursor_controller.move_to(&self.buffer, row, x);
+            self.check_swap_file();
+            return Ok(());
+        }
+        if matches!(self.status_info.get_status(), Status::Modified) {
+            self.status_info
+                .set_message("存在未保存的修改，请先保存".to_string());
+            return Ok(());
+        }
+        let cursor = self.cursor_controller.get_cursor();
+        let position = (cursor.get_y(), cursor.get_x());
+        self.remove_swap_file();
+        self.reset_file_state();
+        let lines = logger::recent_lines();
+        let text: String = lines.iter().map(|line| format!("{}\n", line)).collect();
+        let buffer = TextBuffer::from_text(&text);
+        let rows = buffer.number_of_rows();
+        self.buffer = buffer;
+        let message = match logger::log_path() {
+            Some(path) => format!(
+                "最近 {} 条日志，完整日志位于 {}。F12 = 返回",
+                lines.len(),
+                path.display()
+            ),
+            None => format!("最近 {} 条日志。F12 = 返回", lines.len()),
+        };
+        self.status_info = StatusInfo::new(None, message);
+        self.status_info.set_default_name("[log]");
+        self.status_info.set_status(Status::ReadOnly);
+        // 最新的日志位于末尾
+        self.cursor_controller
+            .move_to(&self.buffer, rows.saturating_sub(1), 0);
+        self.log_viewer = Some(position);
+        Ok(())
+    }
+
     /// 运行编辑器
-    pub fn run(&mut self) {
+    pub fn run(&mut self) -> Result<()> {
+        // 等待输入超时且没有状态变化时不重新绘制，行索引建立期间总行数持续变化
+        let mut dirty = true;
         loop {
-            self.cursor_controller.scroll(&self.editor_view);
+            dirty |= self.handle_job_control()?;
+            if dirty || self.buffer.is_indexing() {
+                self.refresh_screen()?;
+            }
+            if let Some(signal) = terminal_guard::received_signal() {
+                warn!("收到信号：{}", signal);
+                return Err(EditorError::Signal(signal));
+            }
+            dirty = self.is_event_available()?;
+            if dirty && self.handle_event(event::read()?)? {
+                self.remove_swap_file();
+                return Ok(());
+            }
+            self.update_emergency_copy();
+            dirty |= self.check_truncation();
+            dirty |= self.check_external_change();
+            self.update_swap_file();
+        }
+    }
+
+    // 处理外部发送的作业控制信号：SIGTSTP 与 Ctrl-Z 一样挂起编辑器，SIGCONT 后重新进入编辑状态。
+    // 返回是否需要重新绘制
+    fn handle_job_control(&mut self) -> Result<bool> {
+        if terminal_guard::take_suspend_request() {
+            self.suspend()?;
+            return Ok(true);
+        }
+        if terminal_guard::take_continued() {
+            self.editor_view.resume()?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// 刷新屏幕
+    pub fn refresh_screen(&mut self) -> Result<()> {
+        self.check_truncation();
+        if let Some(hex_view) = &mut self.hex_view {
+            hex_view.scroll(self.editor_view.get_win_max_rows());
             self.editor_view
-                .refresh_screen(&mut self.cursor_controller, &self.status_info);
-            let mut exit_flag = false;
-            if self.is_event_available().unwrap() {
-                if let Event::Key(event) = event::read().unwrap() {
-                    debug!("检测到输入事件：{:?}", event);
-                    self.process_key(event, &mut exit_flag);
+                .refresh_hex_screen(hex_view, &self.status_info)
+        } else {
+            self.cursor_controller
+                .scroll(&self.buffer, &self.editor_view);
+            self.editor_view.refresh_screen(
+                &self.buffer,
+                &mut self.cursor_controller,
+                &self.status_info,
+            )
+        }
+    }
+
+    // 检查内存映射的文件是否已被截断，首次检测到截断时光标回到文本开头并返回 true
+    fn check_truncation(&mut self) -> bool {
+        let truncated = self.buffer.check_truncation();
+        if truncated {
+            self.cursor_controller = CursorController::new();
+            self.status_info
+                .set_message("文件已被截断，不再显示文件内容".to_string());
+        }
+        truncated
+    }
+
+    /// 处理一个输入事件，返回编辑器是否应当退出。
+    ///
+    /// 消息栏中正在提示输入时，除挂起外的按键均交给提示输入处理
+    pub fn handle_event(&mut self, event: Event) -> Result<bool> {
+        self.check_truncation();
+        match event {
+            Event::Key(event) => {
+                debug!("检测到输入事件：{:?}", event);
+                let command = Command::from_key(event);
+                if self.prompt.is_some() && command != Some(Command::Suspend) {
+                    self.process_prompt_key(event);
+                    return Ok(false);
+                }
+                if let Some(command) = command {
+                    return self.execute(command);
                 }
             }
+            Event::Resize(_, _) => self.editor_view.update_win_size()?,
+            _ => {}
+        }
+        Ok(false)
+    }
 
-            if exit_flag {
-                break;
+    /// 执行命令，返回编辑器是否应当退出。只读模式下拒绝执行修改文件的命令
+    pub fn execute(&mut self, command: Command) -> Result<bool> {
+        if command.is_edit() && matches!(self.status_info.get_status(), Status::ReadOnly) {
+            self.status_info
+                .set_message("只读模式，无法编辑".to_string());
+            return Ok(false);
+        }
+        match command {
+            Command::Quit => return Ok(true),
+            Command::Suspend => self.suspend()?,
+            Command::Save => self.save(),
+            Command::ReopenWithEncoding => self.start_prompt(
+                "以指定编码重新打开（如 gbk、utf-8、utf-16le、latin1）：",
+                PromptKind::ReopenWithEncoding,
+            ),
+            Command::ConvertEncoding => self.start_prompt(
+                "转换为指定编码（如 gbk、gb18030、utf-8、utf-8-bom）：",
+                PromptKind::ConvertEncoding,
+            ),
+            Command::ConvertLineEnding => {
+                self.start_prompt("转换换行符（lf 或 crlf）：", PromptKind::ConvertLineEnding)
+            }
+            Command::NextFile => self.switch_file(true)?,
+            Command::PreviousFile => self.switch_file(false)?,
+            Command::ShowLog => self.toggle_log_viewer()?,
+            _ if self.hex_view.is_some() => self.execute_hex(command),
+            Command::GotoLine => {
+                self.start_prompt("跳转到（行、行:列、+N/-N 或 N%）：", PromptKind::GotoLine)
+            }
+            Command::ToggleLineNumbers => self.editor_view.toggle_line_number_mode(),
+            Command::ToggleSoftWrap => self.editor_view.toggle_soft_wrap(),
+            Command::Move(Motion::MatchingBracket) => {
+                if !self
+                    .cursor_controller
+                    .move_to_matching_bracket(&self.buffer)
+                {
+                    self.status_info
+                        .set_message("光标处没有可匹配的括号".to_string());
+                }
             }
+            Command::Move(motion) => {
+                self.cursor_controller
+                    .move_cursor(motion, &self.buffer, &self.editor_view)
+            }
+            Command::InsertNewline => {
+                let result = self.cursor_controller.insert_newline(&mut self.buffer);
+                self.finish_edit(result.map(|()| true));
+            }
+            Command::InsertChar(c) => {
+                let result = self.cursor_controller.insert_char(&mut self.buffer, c);
+                self.finish_edit(result.map(|()| true));
+            }
+            Command::DeleteBackward => self.delete(false),
+            Command::DeleteForward => self.delete(true),
+            Command::ToggleHexEditing => {}
         }
+        Ok(false)
+    }
+
+    /// 获取全部文本内容，以内存映射方式只读打开的超大文件返回 None
+    pub fn text(&self) -> Option<String> {
+        self.buffer.text()
+    }
+
+    /// 挂起编辑器并交还 shell，恢复运行后重新获取窗口大小，下一轮循环将完整重绘屏幕
+    fn suspend(&mut self) -> Result<()> {
+        self.editor_view.suspend()?;
+        // 恢复运行时收到的 SIGCONT 已由挂起流程处理
+        terminal_guard::take_continued();
+        Ok(())
+    }
+
+    /// 编辑器异常退出前，将未保存的修改写入交换文件，返回交换文件路径
+    pub fn write_emergency_copy(&self) -> Option<PathBuf> {
+        if !matches!(self.status_info.get_status(), Status::Modified) {
+            return None;
+        }
+        let swap_file = match &self.swap_file {
+            Some(swap_file) => swap_file,
+            None => {
+                warn!("未启用交换文件，无法保存未保存的修改");
+                return None;
+            }
+        };
+        match self.write_swap_file(swap_file) {
+            Ok(_) => Some(swap_file.path().to_path_buf()),
+            Err(_) => None,
+        }
+    }
+
+    // 检查交换文件：其他进程正在编辑该文件时给出警告，存在过期的交换文件时提示恢复
+    fn check_swap_file(&mut self) {
+        if matches!(self.status_info.get_status(), Status::ReadOnly) {
+            return;
+        }
+        let swap_file = SwapFile::for_file(self.status_info.get_file_name());
+        match swap_file.status() {
+            SwapStatus::Missing => self.swap_file = Some(swap_file),
+            SwapStatus::Running(pid) => {
+                warn!("交换文件 {:?} 正被进程 {} 使用", swap_file.path(), pid);
+                let alternate = swap_file.alternate();
+                self.status_info.set_message(format!(
+                    "警告：另一个 z-editor 进程（PID {}）正在编辑该文件，本次编辑的交换文件为 {}",
+                    pid,
+                    alternate.path().display()
+                ));
+                self.swap_file = Some(alternate);
+            }
+            SwapStatus::Stale => {
+                info!("发现过期的交换文件：{:?}", swap_file.path());
+                let label = format!(
+                    "发现交换文件 {}，可能来自崩溃的会话。r = 恢复 | d = 对比 | x = 删除 | Esc = 保留并忽略：",
+                    swap_file.path().display()
+                );
+                self.start_prompt(&label, PromptKind::RecoverSwap);
+            }
+        }
+    }
+
+    // 处理过期交换文件的恢复选项
+    fn recover_swap_file(&mut self, choice: &str) -> std::result::Result<String, String> {
+        let swap_file = SwapFile::for_file(self.status_info.get_file_name());
+        match choice {
+            "r" => {
+                let bytes = swap_file
+                    .read_content()
+                    .map_err(|e| format!("读取交换文件失败：{}", e))?;
+                info!("从交换文件恢复：{:?}", swap_file.path());
+                self.replace_content(bytes);
+                self.swap_file = Some(swap_file);
+                self.mark_modified();
+                Ok("已从交换文件恢复未保存的修改，保存后交换文件将被删除".to_string())
+            }
+            "d" => {
+                let bytes = swap_file
+                    .read_content()
+                    .map_err(|e| format!("读取交换文件失败：{}", e))?;
+                let summary = self.diff_summary("交换文件", &bytes);
+                let label = format!("{}。r = 恢复 | x = 删除 | Esc = 保留并忽略：", summary);
+                self.start_prompt(&label, PromptKind::RecoverSwap);
+                Ok(label)
+            }
+            "x" => {
+                swap_file
+                    .remove()
+                    .map_err(|e| format!("删除交换文件失败：{}", e))?;
+                info!("删除交换文件：{:?}", swap_file.path());
+                self.swap_file = Some(swap_file);
+                Ok("已删除交换文件".to_string())
+            }
+            _ => {
+                self.start_prompt(
+                    "无效的选项。r = 恢复 | d = 对比 | x = 删除 | Esc = 保留并忽略：",
+                    PromptKind::RecoverSwap,
+                );
+                Err("无效的选项".to_string())
+            }
+        }
+    }
+
+    // 保留过期的交换文件，本次编辑改用备用交换文件
+    fn keep_swap_file(&mut self) -> String {
+        let swap_file = SwapFile::for_file(self.status_info.get_file_name());
+        let alternate = swap_file.alternate();
+        info!(
+            "保留交换文件：{:?}，改用备用交换文件：{:?}",
+            swap_file.path(),
+            alternate.path()
+        );
+        let message = format!(
+            "已保留交换文件 {}，本次编辑的交换文件为 {}",
+            swap_file.path().display(),
+            alternate.path().display()
+        );
+        self.swap_file = Some(alternate);
+        message
+    }
+
+    // 对比指定内容与当前内容，返回差异摘要
+    fn diff_summary(&self, name: &str, bytes: &[u8]) -> String {
+        let (other_text, _) = FileEncoding::detect(bytes).decode(bytes);
+        let text = match (&self.hex_view, self.buffer.text()) {
+            (Some(_), _) => return "二进制文件不支持对比".to_string(),
+            (None, None) => return "超大文件不支持对比".to_string(),
+            (None, Some(text)) => text,
+        };
+        let other_lines: Vec<&str> = other_text.lines().collect();
+        let lines: Vec<&str> = text.lines().collect();
+        let first_diff = other_lines
+            .iter()
+            .zip(&lines)
+            .position(|(a, b)| a != b)
+            .or_else(|| {
+                (other_lines.len() != lines.len()).then(|| other_lines.len().min(lines.len()))
+            });
+        match first_diff {
+            None if other_text == text => format!("{}与当前内容相同", name),
+            None => format!("{}与当前内容仅换行符不同", name),
+            Some(row) => format!(
+                "{}共 {} 行，当前内容共 {} 行，首个差异位于第 {} 行",
+                name,
+                other_lines.len(),
+                lines.len(),
+                row + 1
+            ),
+        }
+    }
+
+    // 以新的文件内容替换当前内容，光标尽量停留在原位置
+    fn replace_content(&mut self, bytes: Vec<u8>) {
+        match &mut self.hex_view {
+            Some(hex_view) => *hex_view = HexView::new(bytes),
+            None => {
+                let encoding = FileEncoding::detect(&bytes);
+                let (text, _) = encoding.decode(&bytes);
+                let cursor = self.cursor_controller.get_cursor();
+                let (x, y) = (cursor.get_x(), cursor.get_y());
+                self.buffer = TextBuffer::from_text(&text);
+                self.cursor_controller.move_to(&self.buffer, y, x);
+                self.status_info.set_encoding(encoding);
+            }
+        }
+    }
+
+    // 检查文件是否在外部被修改：未修改的缓冲区自动重新加载，否则提示用户选择处理方式。
+    // 用户取消提示后不再重复提示同一外部修改，保存前仍会再次提示。返回是否提示或重新加载了外部修改
+    fn check_external_change(&mut self) -> bool {
+        if self.prompt.is_some() {
+            return false;
+        }
+        let stamp = match self.read_external_change(true) {
+            Some(stamp) => stamp,
+            None => return false,
+        };
+        if matches!(self.status_info.get_status(), Status::Modified) {
+            self.prompt_external_change(
+                stamp,
+                "文件已在外部修改。r = 重新加载 | k = 保留当前内容 | d = 对比：",
+            );
+        } else {
+            let message = match self.reload() {
+                Ok(_) => "文件已在外部修改，已自动重新加载".to_string(),
+                Err(message) => message,
+            };
+            self.status_info.set_message(message);
+        }
+        true
+    }
+
+    // 提示用户处理外部修改，在用户选择保留当前内容之前保持原文件快照不变
+    fn prompt_external_change(&mut self, stamp: FileStamp, label: &str) {
+        self.external_stamp = Some(stamp);
+        self.start_prompt(label, PromptKind::ExternalChange);
+    }
+
+    // 读取外部修改后的文件快照，文件未被修改时返回 None，`skip_prompted` 为 true 时同样忽略已提示过的修改。
+    // 仅元数据变化而内容不变时直接更新文件快照；文件被删除时停止检查，保存时将重新创建文件
+    fn read_external_change(&mut self, skip_prompted: bool) -> Option<FileStamp> {
+        let stamp = self.file_stamp.as_ref()?;
+        let path = self.status_info.get_file_name()?;
+        let metadata = match fs::metadata(path) {
+            Ok(metadata) => metadata,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                warn!("文件已在外部被删除：{:?}", path);
+                self.file_stamp = None;
+                self.external_stamp = None;
+                self.status_info
+                    .set_message("文件已在外部被删除，保存时将重新创建".to_string());
+                return None;
+            }
+            Err(_) => return None,
+        };
+        let prompted = skip_prompted
+            && self
+                .external_stamp
+                .as_ref()
+                .is_some_and(|external_stamp| external_stamp.matches_metadata(&metadata));
+        if stamp.matches_metadata(&metadata) || prompted {
+            return None;
+        }
+        let (new_stamp, _) = FileStamp::read(path).ok()?;
+        if stamp.same_content(&new_stamp) {
+            self.file_stamp = Some(new_stamp);
+            return None;
+        }
+        info!("文件已在外部修改：{:?}", path);
+        Some(new_stamp)
+    }
+
+    // 重新加载文件内容，丢弃未保存的修改
+    fn reload(&mut self) -> std::result::Result<(), String> {
+        let path = self
+            .status_info
+            .get_file_name()
+            .ok_or("未指定文件名，无法重新加载")?;
+        let (stamp, bytes) = FileStamp::read(path).map_err(|e| format!("读取文件失败：{}", e))?;
+        info!("重新加载文件：{:?}", path);
+        self.file_stamp = Some(stamp);
+        self.external_stamp = None;
+        self.replace_content(bytes);
+        self.status_info.set_status(Status::Saved);
+        self.remove_swap_file();
+        Ok(())
+    }
+
+    // 处理文件在外部被修改后的选项
+    fn resolve_external_change(&mut self, choice: &str) -> std::result::Result<String, String> {
+        match choice {
+            "r" => {
+                self.reload()?;
+                Ok("已重新加载文件".to_string())
+            }
+            "k" => {
+                // 以提示时磁盘上的内容为准，之后的外部修改仍会再次提示
+                if let Some(stamp) = self.external_stamp.take() {
+                    self.file_stamp = Some(stamp);
+                }
+                Ok("已保留当前内容，保存时将覆盖磁盘上的文件".to_string())
+            }
+            "d" => {
+                let path = self
+                    .status_info
+                    .get_file_name()
+                    .ok_or("未指定文件名，无法对比")?;
+                let bytes = fs::read(path).map_err(|e| format!("读取文件失败：{}", e))?;
+                let summary = self.diff_summary("磁盘文件", &bytes);
+                let label = format!("{}。r = 重新加载 | k = 保留当前内容：", summary);
+                self.start_prompt(&label, PromptKind::ExternalChange);
+                Ok(label)
+            }
+            _ => {
+                self.start_prompt(
+                    "无效的选项。r = 重新加载 | k = 保留当前内容 | d = 对比：",
+                    PromptKind::ExternalChange,
+                );
+                Err("无效的选项".to_string())
+            }
+        }
+    }
+
+    // 内容发生变化后定期写入交换文件
+    fn update_swap_file(&mut self) {
+        if !self.swap_pending || self.swap_written_at.elapsed() < SWAP_INTERVAL {
+            return;
+        }
+        if let Some(swap_file) = &self.swap_file {
+            // 写入失败时同样等待下一个周期再重试
+            let _ = self.write_swap_file(swap_file);
+        }
+        self.swap_pending = false;
+        self.swap_written_at = Instant::now();
+    }
+
+    // 登记信号线程使用的应急副本：存在未保存的修改时登记当前内容的快照，否则清除。
+    // 文本缓冲区的快照共享文本内容，只在内容变化后重新登记
+    fn update_emergency_copy(&mut self) {
+        if !matches!(self.status_info.get_status(), Status::Modified) {
+            self.emergency_pending = false;
+            terminal_guard::set_emergency_copy(None);
+            return;
+        }
+        let swap_file = match &self.swap_file {
+            Some(swap_file) if self.emergency_pending => swap_file.clone(),
+            _ => return,
+        };
+        self.emergency_pending = false;
+        let copy: terminal_guard::EmergencyCopy = match &self.hex_view {
+            Some(hex_view) => {
+                let hex_view = hex_view.clone();
+                Box::new(move || {
+                    swap_file.write(|writer| hex_view.write_to(writer))?;
+                    Ok(swap_file.path().to_path_buf())
+                })
+            }
+            None => {
+                let buffer = self.buffer.clone();
+                let encoding = *self.status_info.get_encoding();
+                Box::new(move || {
+                    swap_file.write(|writer| buffer.write_encoded(writer, &encoding))?;
+                    Ok(swap_file.path().to_path_buf())
+                })
+            }
+        };
+        terminal_guard::set_emergency_copy(Some(copy));
+    }
+
+    // 将当前内容写入交换文件
+    fn write_swap_file(&self, swap_file: &SwapFile) -> io::Result<usize> {
+        let result = swap_file.write(|writer| self.write_content(writer));
+        match &result {
+            Ok(len) => debug!("写入交换文件：{:?}，写入字节数：{}", swap_file.path(), len),
+            Err(e) => error!("写入交换文件失败：{:?}，{}", swap_file.path(), e),
+        }
+        result
+    }
+
+    // 内容已保存或正常退出时删除交换文件
+    fn remove_swap_file(&mut self) {
+        if let Some(swap_file) = &self.swap_file {
+            if let Err(e) = swap_file.remove() {
+                error!("删除交换文件失败：{:?}，{}", swap_file.path(), e);
+            }
+        }
+        self.swap_pending = false;
     }
 
     /// 判断是否有按键事件可用
@@ -89,43 +900,332 @@ impl Editor {
         event::poll(Duration::from_millis(500))
     }
 
-    /// 处理按键事件
-    fn process_key(&mut self, event: KeyEvent, exit_flag: &mut bool) {
-        match event {
-            KeyEvent {
-                code: KeyCode::Char('q'),
-                modifiers: KeyModifiers::CONTROL,
-            } => *exit_flag = true,
-            KeyEvent {
-                code:
-                    direction @ (KeyCode::Up
-                    | KeyCode::Down
-                    | KeyCode::Left
-                    | KeyCode::Right
-                    | KeyCode::Home
-                    | KeyCode::End),
-                modifiers: KeyModifiers::NONE,
-            } => self
-                .cursor_controller
-                .move_cursor(direction, &self.editor_view),
-            KeyEvent {
-                code: val @ (KeyCode::PageUp | KeyCode::PageDown),
-                modifiers: KeyModifiers::NONE,
-            } => {
-                if matches!(val, KeyCode::PageUp) {
-                    self.cursor_controller.move_page_up(&self.editor_view);
+    /// 将按键交给消息栏中正在进行的提示输入
+    fn process_prompt_key(&mut self, event: KeyEvent) {
+        let prompt = match &mut self.prompt {
+            Some(prompt) => prompt,
+            None => return,
+        };
+        match prompt.process_key(event) {
+            PromptResult::Pending => self.status_info.set_message(prompt.message()),
+            PromptResult::Done(kind, input) => {
+                self.prompt = None;
+                self.process_prompt(kind, &input);
+            }
+            PromptResult::Cancelled(kind) => {
+                self.prompt = None;
+                self.cancel_prompt(kind);
+            }
+        }
+    }
+
+    /// 在消息栏中开始提示输入
+    fn start_prompt(&mut self, label: &str, kind: PromptKind) {
+        let prompt = Prompt::new(label, kind);
+        self.status_info.set_message(prompt.message());
+        self.prompt = Some(prompt);
+    }
+
+    /// 处理取消提示输入后的操作
+    fn cancel_prompt(&mut self, kind: PromptKind) {
+        let message = match kind {
+            PromptKind::RecoverSwap => self.keep_swap_file(),
+            PromptKind::ExternalChange => "已取消，保存前将再次提示文件已在外部修改".to_string(),
+            _ => "已取消".to_string(),
+        };
+        self.status_info.set_message(message);
+    }
+
+    /// 处理提示输入完成后的操作
+    fn process_prompt(&mut self, kind: PromptKind, input: &str) {
+        let result = match kind {
+            PromptKind::ReopenWithEncoding => self.reopen_with_encoding(input),
+            PromptKind::ConvertEncoding => self.convert_encoding(input),
+            PromptKind::ConvertLineEnding => self.convert_line_ending(input),
+            PromptKind::RecoverSwap => self.recover_swap_file(input),
+            PromptKind::ExternalChange => self.resolve_external_change(input),
+            PromptKind::SaveAs => self.save_as(input),
+            PromptKind::GotoLine => self.goto(input),
+        };
+        let message = match result {
+            Ok(message) | Err(message) => message,
+        };
+        self.status_info.set_message(message);
+    }
+
+    /// 以指定编码重新读取文件，未保存的修改会被拒绝覆盖
+    fn reopen_with_encoding(&mut self, label: &str) -> std::result::Result<String, String> {
+        let encoding =
+            FileEncoding::for_label(label).ok_or_else(|| format!("不支持的编码：{}", label))?;
+        let path = self
+            .status_info
+            .get_file_name()
+            .ok_or("未指定文件名，无法重新打开")?;
+        if matches!(self.status_info.get_status(), Status::Modified) {
+            return Err("存在未保存的修改，请先保存".to_string());
+        }
+        let (file_stamp, bytes) =
+            FileStamp::read(path).map_err(|e| format!("读取文件失败：{}", e))?;
+        let encoding = encoding.with_bom_of(&bytes);
+        let (text, had_errors) = encoding.decode(&bytes);
+        info!("以 {} 编码重新打开文件：{:?}", encoding.name(), path);
+        self.buffer = TextBuffer::from_text(&text);
+        self.cursor_controller = CursorController::new();
+        self.hex_view = None;
+        self.undecodable = had_errors;
+        // 与打开文件时一样，以重新读取的内容作为快照，并丢弃按原编码写入的交换文件
+        self.file_stamp = Some(file_stamp);
+        self.external_stamp = None;
+        self.remove_swap_file();
+        if !matches!(self.status_info.get_status(), Status::ReadOnly) {
+            self.status_info.set_status(Status::Saved);
+        }
+        self.status_info.set_encoding(encoding);
+        if had_errors {
+            Ok(Self::undecodable_message(&encoding))
+        } else {
+            Ok(format!("已以 {} 编码重新打开", encoding.name()))
+        }
+    }
+
+    // 存在无法解码的字节时的提示信息
+    fn undecodable_message(encoding: &FileEncoding) -> String {
+        format!(
+            "警告：部分字节无法以 {} 编码解码，保存前需使用 Alt-E 转换编码，或使用 Alt-R 以其他编码重新打开",
+            encoding.name()
+        )
+    }
+
+    /// 转换文件保存时使用的编码
+    fn convert_encoding(&mut self, label: &str) -> std::result::Result<String, String> {
+        let encoding =
+            FileEncoding::for_label(label).ok_or_else(|| format!("不支持的编码：{}", label))?;
+        let text = match self.buffer.text() {
+            Some(text)
+                if self.hex_view.is_none()
+                    && !matches!(self.status_info.get_status(), Status::ReadOnly) =>
+            {
+                text
+            }
+            _ => return Err("当前文件无法转换编码".to_string()),
+        };
+        encoding.encode(&text).map_err(|e| e.to_string())?;
+        self.undecodable = false;
+        self.status_info.set_encoding(encoding);
+        self.mark_modified();
+        Ok(format!("保存时将使用 {} 编码", encoding.name()))
+    }
+
+    /// 将所有换行符转换为指定风格
+    fn convert_line_ending(&mut self, label: &str) -> std::result::Result<String, String> {
+        let line_ending =
+            LineEnding::for_label(label).ok_or_else(|| format!("不支持的换行符：{}", label))?;
+        if self.hex_view.is_some() || matches!(self.status_info.get_status(), Status::ReadOnly) {
+            return Err("当前文件无法转换换行符".to_string());
+        }
+        if self.buffer.line_ending() != line_ending {
+            self.buffer
+                .convert_line_ending(line_ending)
+                .map_err(|e| e.to_string())?;
+            self.mark_modified();
+        }
+        Ok(format!("换行符已转换为 {}", line_ending.name()))
+    }
+
+    /// 跳转到指定位置，并使目标行位于可见区域中央
+    fn goto(&mut self, input: &str) -> std::result::Result<String, String> {
+        let current_row = self.cursor_controller.get_cursor().get_y();
+        let (row, column) =
+            Self::parse_goto_target(input, current_row, self.buffer.number_of_rows())?;
+        self.cursor_controller
+            .move_to_char(&self.buffer, row, column);
+        self.cursor_controller
+            .center_cursor(&self.buffer, &self.editor_view);
+        Ok(format!("已跳转到第 {} 行", row + 1))
+    }
+
+    /// 解析跳转位置，返回从 0 开始的 (行, 列)。
+    ///
+    /// 支持 `行`、`行:列`、相对当前行的 `+N`/`-N` 以及按总行数计算的 `N%`，行号超出范围时停留在末行
+    fn parse_goto_target(
+        input: &str,
+        current_row: usize,
+        rows: usize,
+    ) -> std::result::Result<(usize, usize), String> {
+        let invalid = || format!("无效的跳转位置：{}", input);
+        let last_row = rows.saturating_sub(1);
+        if input.is_empty() {
+            return Err("未输入跳转位置".to_string());
+        }
+        if let Some(percent) = input.strip_suffix('%') {
+            let percent: usize = percent.parse().map_err(|_| invalid())?;
+            if percent > 100 {
+                return Err(format!("百分比超出范围：{}", input));
+            }
+            // 与 vim 的 N% 相同，按总行数向上取整
+            let line = (percent * rows).div_ceil(100);
+            return Ok((line.saturating_sub(1), 0));
+        }
+        let parse_number = |text: &str| text.parse::<usize>().map_err(|_| invalid());
+        if let Some(offset) = input.strip_prefix('+') {
+            let row = current_row.saturating_add(parse_number(offset)?);
+            return Ok((row.min(last_row), 0));
+        }
+        if let Some(offset) = input.strip_prefix('-') {
+            return Ok((current_row.saturating_sub(parse_number(offset)?), 0));
+        }
+        let (line, column) = match input.split_once(':') {
+            Some((line, column)) => (line, parse_number(column)?),
+            None => (input, 1),
+        };
+        let line = parse_number(line)?;
+        if line == 0 || column == 0 {
+            return Err("行号与列号从 1 开始".to_string());
+        }
+        Ok(((line - 1).min(last_row), column - 1))
+    }
+
+    /// 在十六进制视图下执行命令
+    fn execute_hex(&mut self, command: Command) {
+        let win_max_rows = self.editor_view.get_win_max_rows();
+        let hex_view = match &mut self.hex_view {
+            Some(hex_view) => hex_view,
+            None => return,
+        };
+        match command {
+            Command::ToggleHexEditing => {
+                hex_view.toggle_editing();
+                let message = if hex_view.is_editing() {
+                    "十六进制编辑已开启，输入 0-9 a-f 改写光标处的字节"
                 } else {
-                    self.cursor_controller.move_page_down(&self.editor_view);
+                    "十六进制编辑已关闭"
+                };
+                self.status_info.set_message(message.to_string());
+            }
+            Command::Move(motion) => hex_view.move_cursor(motion, win_max_rows),
+            Command::GotoLine => self
+                .status_info
+                .set_message("十六进制视图不支持跳转到行".to_string()),
+            Command::InsertChar(c) => {
+                if hex_view.input_hex_digit(c) {
+                    self.mark_modified();
+                } else if !hex_view.is_editing() {
+                    self.status_info
+                        .set_message("按 Ctrl-E 开启十六进制编辑".to_string());
                 }
             }
             _ => {}
         }
     }
+
+    /// 保存文件，未指定文件名时提示另存为
+    fn save(&mut self) {
+        if let Some(stamp) = self.read_external_change(false) {
+            self.prompt_external_change(
+                stamp,
+                "保存前发现文件已在外部修改。r = 重新加载 | k = 保留当前内容（再次保存将覆盖）| d = 对比：",
+            );
+            return;
+        }
+        let path = match self.status_info.get_file_name() {
+            Some(path) => path.to_path_buf(),
+            None => return self.start_prompt("另存为：", PromptKind::SaveAs),
+        };
+        let message = match self.save_to(&path) {
+            Ok(len) => format!("已写入 {} 字节", len),
+            Err(e) => format!("保存失败：{}", e),
+        };
+        self.status_info.set_message(message);
+    }
+
+    // 将未命名的内容另存为指定文件，不覆盖已存在的文件
+    fn save_as(&mut self, input: &str) -> std::result::Result<String, String> {
+        if input.is_empty() {
+            return Err("未指定文件名，无法保存".to_string());
+        }
+        let path = PathBuf::from(input);
+        if path.exists() {
+            return Err(format!("文件已存在：{}，请使用其他文件名", path.display()));
+        }
+        let len = self
+            .save_to(&path)
+            .map_err(|e| format!("保存失败：{}", e))?;
+        self.status_info.set_file_name(path.clone());
+        let file = FileArg::new(Some(path.clone()), None, None);
+        match self.files.get_mut(self.file_index) {
+            Some(current) => *current = file,
+            None => self.files.push(file),
+        }
+        // 交换文件随文件名变化
+        self.swap_file = None;
+        self.check_swap_file();
+        Ok(format!("已另存为 {}，写入 {} 字节", path.display(), len))
+    }
+
+    // 将当前内容写入指定文件，成功后更新文件快照与文件状态，返回写入的字节数
+    fn save_to(&mut self, path: &Path) -> io::Result<usize> {
+        if self.undecodable {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "部分字节无法解码，保存会以替换字符覆盖原内容，请先使用 Alt-E 转换编码",
+            ));
+        }
+        let len = self.write_to_file(path)?;
+        info!("保存文件：{:?}，写入字节数：{}", path, len);
+        self.file_stamp = FileStamp::read(path).ok().map(|(stamp, _)| stamp);
+        self.external_stamp = None;
+        self.status_info.set_status(Status::Saved);
+        self.remove_swap_file();
+        Ok(len)
+    }
+
+    // 将当前内容写入指定文件，十六进制视图下原样写入字节
+    fn write_to_file(&self, path: &Path) -> io::Result<usize> {
+        match &self.hex_view {
+            Some(hex_view) => hex_view.save(path),
+            None => self.buffer.save(path, self.status_info.get_encoding()),
+        }
+    }
+
+    // 将当前内容按保存时的格式写入输出流
+    fn write_content(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        match &self.hex_view {
+            Some(hex_view) => hex_view.write_to(writer),
+            None => self
+                .buffer
+                .write_encoded(writer, self.status_info.get_encoding()),
+        }
+    }
+
+    // 标记内容已修改，等待写入交换文件
+    fn mark_modified(&mut self) {
+        self.status_info.set_status(Status::Modified);
+        self.swap_pending = true;
+        self.emergency_pending = true;
+    }
+
+    /// 删除光标前或光标处的内容，`forward` 为 true 时删除光标处内容
+    fn delete(&mut self, forward: bool) {
+        let result = if forward {
+            self.cursor_controller.delete_forward(&mut self.buffer)
+        } else {
+            self.cursor_controller.delete_backward(&mut self.buffer)
+        };
+        self.finish_edit(result);
+    }
+
+    // 处理编辑操作的结果：内容发生变化时标记为已修改，只读缓冲区拒绝编辑时提示用户
+    fn finish_edit(&mut self, result: std::result::Result<bool, ReadOnlyError>) {
+        match result {
+            Ok(true) => self.mark_modified(),
+            Ok(false) => {}
+            Err(e) => self.status_info.set_message(e.to_string()),
+        }
+    }
 }
 
 impl Drop for Editor {
     fn drop(&mut self) {
-        terminal::disable_raw_mode().expect("无法关闭 Raw 模式");
         info!("关闭编辑器")
     }
 }
@@ -1,46 +1,79 @@
 use std::io;
-use std::io::{stdout, Write};
+use std::io::{stdout, Stdout, Write};
 
-/// 编辑器输出
-pub struct EditorOutput {
+/// 开始同步更新（DEC 模式 2026），终端在结束前暂停刷新显示
+const BEGIN_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026h";
+/// 结束同步更新，终端一次性显示期间收到的全部内容
+const END_SYNCHRONIZED_UPDATE: &[u8] = b"\x1b[?2026l";
+
+/// 编辑器输出。
+///
+/// 一帧的全部内容先写入字节缓冲区，刷新时一次写出到输出流（默认为标准输出），
+/// 终端支持同步更新时以同步更新包裹整帧，避免终端显示绘制到一半的画面
+pub struct EditorOutput<W: Write = Stdout> {
+    // 输出流
+    writer: W,
     // 输出内容缓冲区
-    content: String,
+    buffer: Vec<u8>,
+    // 是否使用同步更新
+    synchronized: bool,
+}
+
+impl Default for EditorOutput {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl EditorOutput {
-    /// 创建一个编辑器内容输出
+    /// 创建一个输出到标准输出的编辑器内容输出
     pub fn new() -> Self {
+        Self::with_writer(stdout())
+    }
+}
+
+impl<W: Write> EditorOutput<W> {
+    /// 创建一个输出到指定输出流的编辑器内容输出
+    pub fn with_writer(writer: W) -> Self {
         Self {
-            content: String::new(),
+            writer,
+            buffer: Vec::new(),
+            synchronized: false,
         }
     }
 
-    /// 添加一个字符到编辑器内容输出缓冲区
-    pub fn push(&mut self, ch: char) {
-        self.content.push(ch)
+    /// 设置是否使用同步更新
+    pub fn set_synchronized(&mut self, synchronized: bool) {
+        self.synchronized = synchronized;
     }
 
-    /// 添加一个字符串到编辑器内容输出缓冲区
-    pub fn push_str(&mut self, string: &str) {
-        self.content.push_str(string)
+    /// 获取输出流
+    pub fn get_ref(&self) -> &W {
+        &self.writer
     }
 }
 
-impl Write for EditorOutput {
+impl<W: Write> Write for EditorOutput<W> {
+    // 缓冲区只保存字节，不完整的 UTF-8 序列会与后续写入的内容拼接完整
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        match std::str::from_utf8(buf) {
-            Ok(s) => {
-                self.content.push_str(s);
-                Ok(s.len())
-            }
-            Err(_) => Err(io::ErrorKind::WriteZero.into()),
-        }
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        let out = write!(stdout(), "{}", self.content);
-        stdout().flush()?;
-        self.content.clear();
-        out
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        if self.synchronized {
+            self.buffer
+                .splice(0..0, BEGIN_SYNCHRONIZED_UPDATE.iter().copied());
+            self.buffer.extend_from_slice(END_SYNCHRONIZED_UPDATE);
+        }
+        let result = self
+            .writer
+            .write_all(&self.buffer)
+            .and_then(|_| self.writer.flush());
+        self.buffer.clear();
+        result
     }
 }
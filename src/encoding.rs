@@ -0,0 +1,148 @@
+use std::io;
+
+use encoding_rs::{Encoding, GB18030, GBK, UTF_16BE, UTF_16LE, UTF_8, WINDOWS_1252};
+
+/// UTF-8 BOM
+const UTF8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// 文件编码
+#[derive(Clone, Copy)]
+pub struct FileEncoding {
+    // 编码
+    encoding: &'static Encoding,
+    // 保存时是否写入 BOM
+    bom: bool,
+}
+
+impl FileEncoding {
+    /// 不带 BOM 的 UTF-8 编码
+    pub fn utf8() -> Self {
+        Self {
+            encoding: UTF_8,
+            bom: false,
+        }
+    }
+
+    /// 根据名称获取编码，支持 `utf-8-bom` 以及 WHATWG 定义的编码名称，例如 `gbk`、`gb18030`、`utf-16le`、`latin1`。
+    ///
+    /// 用于转换编码，UTF-16 编码保存时写入 BOM。以指定编码重新打开文件时应再调用 [`FileEncoding::with_bom_of`]
+    pub fn for_label(label: &str) -> Option<Self> {
+        let label = label.trim().to_ascii_lowercase();
+        if label == "utf-8-bom" || label == "utf8-bom" {
+            return Some(Self {
+                encoding: UTF_8,
+                bom: true,
+            });
+        }
+        Encoding::for_label(label.as_bytes()).map(|encoding| Self {
+            encoding,
+            // UTF-16 文件总是带有 BOM
+            bom: encoding == UTF_16LE || encoding == UTF_16BE,
+        })
+    }
+
+    /// 检测文件编码：优先根据 BOM 判断，其次依次尝试 UTF-8 与 GB18030，均失败时视为 Latin-1。
+    ///
+    /// GB18030 兼容 GBK，内容中的字节序列均属于 GBK 时视为 GBK，包含 GB18030 四字节序列时视为 GB18030
+    pub fn detect(bytes: &[u8]) -> Self {
+        if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+            return Self {
+                encoding,
+                bom: true,
+            };
+        }
+        let encoding = if UTF_8
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .is_some()
+        {
+            UTF_8
+        } else {
+            match GB18030.decode_without_bom_handling_and_without_replacement(bytes) {
+                Some(text) if Self::is_gbk(&text, bytes) => GBK,
+                Some(_) => GB18030,
+                None => WINDOWS_1252,
+            }
+        };
+        Self {
+            encoding,
+            bom: false,
+        }
+    }
+
+    // 按 GB18030 解码的内容是否均为 GBK 字节序列，即以 GBK 重新编码后与原内容一致。
+    // encoding_rs 的 GBK 解码器同样接受 GB18030 的四字节序列，但 GBK 编码器无法写回这些字符
+    fn is_gbk(text: &str, bytes: &[u8]) -> bool {
+        let (encoded, _, had_errors) = GBK.encode(text);
+        !had_errors && encoded == bytes
+    }
+
+    /// 按内容开头是否存在该编码的 BOM 决定保存时是否写入 BOM，保持原文件的 BOM 状态
+    pub fn with_bom_of(self, bytes: &[u8]) -> Self {
+        Self {
+            bom: matches!(Encoding::for_bom(bytes), Some((encoding, _)) if encoding == self.encoding),
+            ..self
+        }
+    }
+
+    /// 内容是否以 BOM 开头
+    pub fn has_bom(bytes: &[u8]) -> bool {
+        Encoding::for_bom(bytes).is_some()
+    }
+
+    /// 获取编码名称
+    pub fn name(&self) -> String {
+        if self.bom && self.encoding == UTF_8 {
+            format!("{} BOM", self.encoding.name())
+        } else {
+            self.encoding.name().to_string()
+        }
+    }
+
+    /// 是否为不带 BOM 的 UTF-8 编码，此时内容无需转换即可写入
+    pub fn is_plain_utf8(&self) -> bool {
+        self.encoding == UTF_8 && !self.bom
+    }
+
+    /// 将字节解码为文本，BOM 不计入文本内容。返回解码后的文本以及是否存在无法解码的字节
+    pub fn decode(&self, bytes: &[u8]) -> (String, bool) {
+        let bytes = match Encoding::for_bom(bytes) {
+            Some((encoding, bom_len)) if encoding == self.encoding => &bytes[bom_len..],
+            _ => bytes,
+        };
+        let (text, had_errors) = self.encoding.decode_without_bom_handling(bytes);
+        (text.into_owned(), had_errors)
+    }
+
+    /// 将文本编码为字节，文本中存在该编码无法表示的字符时返回错误
+    pub fn encode(&self, text: &str) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::with_capacity(text.len() + UTF8_BOM.len());
+        if self.encoding == UTF_16LE || self.encoding == UTF_16BE {
+            // encoding_rs 不支持编码为 UTF-16，需要手动转换
+            let little_endian = self.encoding == UTF_16LE;
+            for unit in std::iter::once(0xFEFF)
+                .filter(|_| self.bom)
+                .chain(text.encode_utf16())
+            {
+                let unit_bytes = if little_endian {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                };
+                bytes.extend_from_slice(&unit_bytes);
+            }
+            return Ok(bytes);
+        }
+        if self.bom {
+            bytes.extend_from_slice(UTF8_BOM);
+        }
+        let (encoded, _, had_errors) = self.encoding.encode(text);
+        if had_errors {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("文本中存在 {} 编码无法表示的字符", self.encoding.name()),
+            ));
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+}
@@ -0,0 +1,57 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::path::PathBuf;
+
+/// 编辑器错误
+#[derive(Debug)]
+pub enum EditorError {
+    /// 终端操作失败，例如无法获取窗口大小或写入输出
+    Terminal(io::Error),
+    /// 文件读写失败
+    File(PathBuf, io::Error),
+    /// 收到终止信号
+    Signal(i32),
+}
+
+/// 编辑器操作结果
+pub type Result<T> = std::result::Result<T, EditorError>;
+
+impl EditorError {
+    /// 创建文件读写错误
+    pub fn file(path: impl Into<PathBuf>, error: io::Error) -> Self {
+        EditorError::File(path.into(), error)
+    }
+
+    /// 进程退出码，因信号退出时遵循 shell 惯例返回 128 + 信号值
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            EditorError::Signal(signal) => 128 + signal,
+            _ => 1,
+        }
+    }
+}
+
+impl Display for EditorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EditorError::Terminal(e) => write!(f, "终端操作失败：{}", e),
+            EditorError::File(path, e) => write!(f, "{}：{}", path.display(), e),
+            EditorError::Signal(signal) => write!(f, "收到信号 {}，编辑器已退出", signal),
+        }
+    }
+}
+
+impl std::error::Error for EditorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EditorError::Terminal(e) | EditorError::File(_, e) => Some(e),
+            EditorError::Signal(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for EditorError {
+    fn from(e: io::Error) -> Self {
+        EditorError::Terminal(e)
+    }
+}
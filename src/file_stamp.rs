@@ -0,0 +1,50 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::fs::Metadata;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::path::Path;
+use std::time::SystemTime;
+
+/// 文件快照。
+///
+/// 记录文件的修改时间、大小与内容哈希，用于检测文件是否在外部被修改。
+/// 修改时间或大小发生变化时才需要重新读取文件比较哈希
+pub struct FileStamp {
+    // 修改时间
+    modified: Option<SystemTime>,
+    // 文件大小
+    len: u64,
+    // 内容哈希
+    hash: u64,
+}
+
+impl FileStamp {
+    /// 根据文件元数据与内容创建快照
+    pub fn new(metadata: &Metadata, bytes: &[u8]) -> Self {
+        let mut hasher = DefaultHasher::new();
+        bytes.hash(&mut hasher);
+        Self {
+            modified: metadata.modified().ok(),
+            len: metadata.len(),
+            hash: hasher.finish(),
+        }
+    }
+
+    /// 读取文件并创建快照，同时返回文件内容
+    pub fn read(path: &Path) -> io::Result<(Self, Vec<u8>)> {
+        let metadata = fs::metadata(path)?;
+        let bytes = fs::read(path)?;
+        Ok((Self::new(&metadata, &bytes), bytes))
+    }
+
+    /// 文件元数据是否与快照一致
+    pub fn matches_metadata(&self, metadata: &Metadata) -> bool {
+        self.modified == metadata.modified().ok() && self.len == metadata.len()
+    }
+
+    /// 文件内容是否与另一快照一致
+    pub fn same_content(&self, other: &FileStamp) -> bool {
+        self.len == other.len && self.hash == other.hash
+    }
+}
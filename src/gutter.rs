@@ -0,0 +1,63 @@
+use std::cmp;
+
+/// 标记列宽度，预留给诊断信息、git 变更等标记使用
+const SIGN_COLUMN_WIDTH: usize = 1;
+/// 行号与文本之间的间隔宽度
+const SEPARATOR_WIDTH: usize = 1;
+
+/// 行号显示模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineNumberMode {
+    /// 绝对行号
+    Absolute,
+    /// 相对行号，光标所在行显示绝对行号
+    Relative,
+}
+
+/// 文本左侧的行号栏
+pub struct Gutter {
+    // 行号显示模式
+    mode: LineNumberMode,
+}
+
+impl Gutter {
+    /// 创建行号栏
+    pub fn new(mode: LineNumberMode) -> Self {
+        Self { mode }
+    }
+
+    /// 切换绝对行号与相对行号
+    pub fn toggle_mode(&mut self) {
+        self.mode = match self.mode {
+            LineNumberMode::Absolute => LineNumberMode::Relative,
+            LineNumberMode::Relative => LineNumberMode::Absolute,
+        };
+    }
+
+    /// 根据文本总行数计算行号栏宽度
+    pub fn width(&self, number_of_rows: usize) -> usize {
+        SIGN_COLUMN_WIDTH + Self::number_width(number_of_rows) + SEPARATOR_WIDTH
+    }
+
+    /// 渲染指定行的行号栏内容，`row` 与 `cursor_row` 均从 0 开始
+    pub fn render(&self, row: usize, cursor_row: usize, number_of_rows: usize) -> String {
+        let number = match self.mode {
+            LineNumberMode::Relative if row != cursor_row => row.abs_diff(cursor_row),
+            _ => row + 1,
+        };
+        format!(
+            "{:sign$}{:>num$}{:sep$}",
+            "",
+            number,
+            "",
+            sign = SIGN_COLUMN_WIDTH,
+            num = Self::number_width(number_of_rows),
+            sep = SEPARATOR_WIDTH
+        )
+    }
+
+    // 行号数字部分宽度，最少预留 3 位避免行数变化时频繁抖动
+    fn number_width(number_of_rows: usize) -> usize {
+        cmp::max(number_of_rows.max(1).to_string().len(), 3)
+    }
+}
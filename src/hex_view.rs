@@ -0,0 +1,178 @@
+use std::cmp;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::io::Write;
+use std::path::Path;
+
+use crate::command::Motion;
+
+/// 每行显示的字节数
+const BYTES_PER_ROW: usize = 16;
+/// 偏移量列与十六进制列的宽度之和
+const HEX_COLUMN_START: usize = 10;
+/// 用于判断文件是否为二进制文件的检测长度
+const DETECT_LEN: usize = 8000;
+
+/// 判断内容是否为二进制数据：前若干字节中包含 NUL 字节。UTF-16 文本同样包含 NUL 字节，需先通过 BOM 排除
+pub fn is_binary(bytes: &[u8]) -> bool {
+    bytes[..cmp::min(bytes.len(), DETECT_LEN)].contains(&0)
+}
+
+/// 十六进制视图。
+///
+/// 每行依次显示偏移量、十六进制字节与 ASCII 字符，光标按字节移动，开启编辑后可按半字节改写内容
+#[derive(Clone)]
+pub struct HexView {
+    // 文件原始字节
+    bytes: Vec<u8>,
+    // 光标所在字节位置
+    cursor: usize,
+    // 光标是否位于字节的低半字节
+    low_nibble: bool,
+    // 行偏移量
+    rows_offset: usize,
+    // 是否开启十六进制编辑
+    editing: bool,
+}
+
+impl HexView {
+    /// 创建十六进制视图
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self {
+            bytes,
+            cursor: 0,
+            low_nibble: false,
+            rows_offset: 0,
+            editing: false,
+        }
+    }
+
+    /// 获取总行数
+    pub fn number_of_rows(&self) -> usize {
+        self.bytes.len().div_ceil(BYTES_PER_ROW)
+    }
+
+    /// 获取总字节数
+    pub fn number_of_bytes(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// 获取光标所在行
+    pub fn cursor_row(&self) -> usize {
+        self.cursor / BYTES_PER_ROW
+    }
+
+    /// 获取行偏移量
+    pub fn get_rows_offset(&self) -> usize {
+        self.rows_offset
+    }
+
+    /// 是否开启十六进制编辑
+    pub fn is_editing(&self) -> bool {
+        self.editing
+    }
+
+    /// 切换十六进制编辑
+    pub fn toggle_editing(&mut self) {
+        self.editing = !self.editing;
+        self.low_nibble = false;
+    }
+
+    /// 渲染指定行：偏移量、十六进制字节以及 ASCII 字符，不可打印字符以 `.` 显示
+    pub fn render_row(&self, i: usize) -> String {
+        let start = i * BYTES_PER_ROW;
+        let chunk = &self.bytes[start..cmp::min(start + BYTES_PER_ROW, self.bytes.len())];
+        let mut row = format!("{:08x}  ", start);
+        for col in 0..BYTES_PER_ROW {
+            if col == BYTES_PER_ROW / 2 {
+                row.push(' ');
+            }
+            match chunk.get(col) {
+                Some(b) => write!(row, "{:02x} ", b).unwrap(),
+                None => row.push_str("   "),
+            }
+        }
+        row.push_str(" |");
+        chunk.iter().for_each(|&b| {
+            row.push(if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            })
+        });
+        row.push('|');
+        row
+    }
+
+    /// 获取光标在屏幕中的位置，返回 (列, 行)
+    pub fn screen_position(&self) -> (usize, usize) {
+        let col = self.cursor % BYTES_PER_ROW;
+        let x = HEX_COLUMN_START
+            + col * 3
+            + usize::from(col >= BYTES_PER_ROW / 2)
+            + usize::from(self.low_nibble);
+        (x, self.cursor_row() - self.rows_offset)
+    }
+
+    /// 屏幕滚动，保证光标所在行可见
+    pub fn scroll(&mut self, win_max_rows: usize) {
+        let row = self.cursor_row();
+        if row < self.rows_offset {
+            self.rows_offset = row;
+        } else if row >= self.rows_offset + win_max_rows {
+            self.rows_offset = row + 1 - win_max_rows;
+        }
+    }
+
+    /// 按字节移动光标
+    pub fn move_cursor(&mut self, motion: Motion, win_max_rows: usize) {
+        let last = self.bytes.len().saturating_sub(1);
+        let row_start = self.cursor - self.cursor % BYTES_PER_ROW;
+        self.cursor = match motion {
+            Motion::Left => self.cursor.saturating_sub(1),
+            Motion::Right => self.cursor + 1,
+            Motion::Up => self.cursor.saturating_sub(BYTES_PER_ROW),
+            Motion::Down if self.cursor + BYTES_PER_ROW <= last => self.cursor + BYTES_PER_ROW,
+            Motion::Home => row_start,
+            Motion::End => row_start + BYTES_PER_ROW - 1,
+            Motion::PageUp => self.cursor.saturating_sub(BYTES_PER_ROW * win_max_rows),
+            Motion::PageDown => self.cursor + BYTES_PER_ROW * win_max_rows,
+            Motion::BufferStart => 0,
+            Motion::BufferEnd => last,
+            _ => self.cursor,
+        };
+        self.cursor = cmp::min(self.cursor, last);
+        self.low_nibble = false;
+    }
+
+    /// 在光标处输入十六进制数字改写当前半字节。未开启编辑或输入不是十六进制数字时返回 false
+    pub fn input_hex_digit(&mut self, c: char) -> bool {
+        let digit = match c.to_digit(16) {
+            Some(digit) if self.editing && !self.bytes.is_empty() => digit as u8,
+            _ => return false,
+        };
+        let byte = &mut self.bytes[self.cursor];
+        if self.low_nibble {
+            *byte = (*byte & 0xf0) | digit;
+            self.low_nibble = false;
+            self.cursor = cmp::min(self.cursor + 1, self.bytes.len() - 1);
+        } else {
+            *byte = (*byte & 0x0f) | (digit << 4);
+            self.low_nibble = true;
+        }
+        true
+    }
+
+    /// 将字节原样写入输出流，返回写入的字节数
+    pub fn write_to(&self, mut writer: impl Write) -> io::Result<usize> {
+        writer.write_all(&self.bytes)?;
+        Ok(self.bytes.len())
+    }
+
+    /// 将字节原样保存至指定文件，返回写入的字节数
+    pub fn save(&self, path: &Path) -> io::Result<usize> {
+        fs::write(path, &self.bytes)?;
+        Ok(self.bytes.len())
+    }
+}
@@ -0,0 +1,82 @@
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::unicode::grapheme_width;
+use crate::TAB_SIZE;
+
+/// 制表符与显示宽度策略。
+///
+/// 决定行内字节位置与渲染列之间的换算：制表符展开至下一个制表位，宽字符占两列，组合字符不占列
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Layout {
+    // 制表符大小
+    tab_size: usize,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self::new(TAB_SIZE)
+    }
+}
+
+impl Layout {
+    /// 创建指定制表符大小的策略，制表符大小至少为 1
+    pub fn new(tab_size: usize) -> Self {
+        Self {
+            tab_size: tab_size.max(1),
+        }
+    }
+
+    /// 获取制表符大小
+    pub fn tab_size(&self) -> usize {
+        self.tab_size
+    }
+
+    /// 计算字形簇渲染后的下一个渲染列
+    pub fn advance(&self, render_x: usize, grapheme: &str) -> usize {
+        if grapheme == "\t" {
+            render_x + self.tab_size - render_x % self.tab_size
+        } else {
+            render_x + grapheme_width(grapheme)
+        }
+    }
+
+    /// 计算行内字节位置对应的渲染列，字节位置超出行末或位于字符中间时取其之前最近的字符边界
+    pub fn render_x(&self, raw_content: &str, raw_x: usize) -> usize {
+        raw_content[..raw_content.floor_char_boundary(raw_x)]
+            .graphemes(true)
+            .fold(0, |render_x, grapheme| self.advance(render_x, grapheme))
+    }
+
+    /// 计算渲染列对应的行内字节位置，渲染列位于宽字符或制表符中间时取其起始位置，超出行末时取行末
+    pub fn raw_x(&self, raw_content: &str, render_x: usize) -> usize {
+        let mut current = 0;
+        for (i, grapheme) in raw_content.grapheme_indices(true) {
+            current = self.advance(current, grapheme);
+            if current > render_x {
+                return i;
+            }
+        }
+        raw_content.len()
+    }
+
+    /// 获取行渲染后所占列数
+    pub fn width(&self, raw_content: &str) -> usize {
+        self.render_x(raw_content, raw_content.len())
+    }
+
+    /// 渲染行内容，制表符以空格展开至下一个制表位
+    pub fn render(&self, raw_content: &str) -> String {
+        let mut rendered_content = String::with_capacity(raw_content.len());
+        let mut column = 0;
+        for grapheme in raw_content.graphemes(true) {
+            let next = self.advance(column, grapheme);
+            if grapheme == "\t" {
+                rendered_content.extend(std::iter::repeat_n(' ', next - column));
+            } else {
+                rendered_content.push_str(grapheme);
+            }
+            column = next;
+        }
+        rendered_content
+    }
+}
@@ -0,0 +1,112 @@
+//! z-editor：终端文本编辑器。
+//!
+//! 编辑器的各部分均可单独使用：
+//!
+//! - [`TextBuffer`]：按行访问的文本缓冲区，提供插入、删除、拆分与合并行等编辑操作，
+//!   以及按字形簇、单词、段落与括号计算的光标移动（见 [`motion`]）
+//! - [`Layout`]：制表符与显示宽度策略，决定行内位置与渲染列之间的换算
+//! - [`CursorController`]：在 [`TextBuffer`] 上移动光标并执行编辑，软换行、翻页与滚动需配合 [`EditorView`]
+//! - [`EditorView`]：将 [`TextBuffer`]、行号栏、状态栏与消息栏渲染为 [`Frame`] 并交给 [`Backend`] 输出
+//! - [`Command`]：按键到编辑器命令的映射，由 [`Editor::execute`] 执行
+//! - [`Editor`]：组合以上部分的完整编辑器
+//!
+//! 使用 [`HeadlessBackend`] 可以在没有终端的环境中运行编辑器并读取渲染结果：
+//!
+//! ```
+//! use z_editor::{Command, Editor, HeadlessBackend, Motion};
+//!
+//! let backend = HeadlessBackend::new(40, 10);
+//! let mut editor = Editor::open(None, Box::new(backend.clone())).unwrap();
+//! for c in "hello".chars() {
+//!     editor.execute(Command::InsertChar(c)).unwrap();
+//! }
+//! editor.execute(Command::Move(Motion::Home)).unwrap();
+//! editor.refresh_screen().unwrap();
+//!
+//! assert_eq!(editor.text().as_deref(), Some("hello"));
+//! assert_eq!(backend.row_text(0), "   1 hello");
+//! assert_eq!(backend.cursor(), Some((5, 0)));
+//! ```
+//!
+//! 光标移动不依赖后端，可直接在 [`TextBuffer`] 上使用：
+//!
+//! ```
+//! use z_editor::{CursorController, Position, TextBuffer};
+//!
+//! let mut buffer = TextBuffer::from_text("fn main() {\n\tprintln!(\"你好\");\n}\n");
+//! assert_eq!(buffer.word_forward(Position::new(0, 0)), Position::new(0, 3));
+//! assert_eq!(buffer.matching_bracket(Position::new(0, 10)), Some(Position::new(2, 0)));
+//!
+//! let mut cursor = CursorController::new();
+//! // 制表符展开为 8 列，渲染列 16 位于 `println!` 之后
+//! cursor.move_to(&buffer, 1, 16);
+//! cursor.insert_char(&mut buffer, '!').unwrap();
+//! assert_eq!(buffer.row(1), "\tprintln!!(\"你好\");");
+//! ```
+
+#![warn(missing_docs)]
+
+#[macro_use]
+extern crate log;
+
+/// 终端后端：输出画面并提供窗口大小
+pub mod backend;
+/// 文本缓冲区与换行符风格
+pub mod buffer;
+/// 命令行参数解析
+pub mod cli;
+/// 编辑器命令与按键映射
+pub mod command;
+/// 编辑器配置
+pub mod config;
+/// 光标移动与编辑
+pub mod cursor_controller;
+/// 编辑器
+pub mod editor;
+/// 带缓冲与同步更新的终端输出
+pub mod editor_output;
+/// 文件编码的检测与转换
+pub mod encoding;
+/// 编辑器错误
+pub mod error;
+mod file_stamp;
+mod gutter;
+/// 二进制文件的十六进制视图
+pub mod hex_view;
+/// 制表符与显示宽度策略
+pub mod layout;
+/// 日志：写入按大小轮转的日志文件，并在内存中保留最近的日志供编辑器内查看
+pub mod logger;
+/// 以内存映射方式只读打开的超大文件
+pub mod mapped_file;
+/// 文本缓冲区上的光标移动
+pub mod motion;
+mod prompt;
+/// 按键脚本回放
+pub mod replay;
+/// 屏幕帧与差异输出
+pub mod screen;
+/// 文件名、文件状态与消息栏信息
+pub mod status;
+mod swap;
+/// 终端状态的进入、恢复与信号处理
+pub mod terminal_guard;
+mod unicode;
+/// 编辑视图
+pub mod view;
+
+pub use crate::backend::{Backend, CrosstermBackend, HeadlessBackend};
+pub use crate::buffer::{LineEnding, TextBuffer};
+pub use crate::command::{Command, Motion};
+pub use crate::cursor_controller::CursorController;
+pub use crate::editor::Editor;
+pub use crate::error::{EditorError, Result};
+pub use crate::layout::Layout;
+pub use crate::motion::Position;
+pub use crate::screen::Frame;
+pub use crate::view::EditorView;
+
+/// 编辑器版本
+pub const VERSION: &str = "0.0.1";
+/// 制表符大小
+pub const TAB_SIZE: usize = 8;
@@ -0,0 +1,212 @@
+use std::collections::VecDeque;
+use std::env;
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::{Mutex, OnceLock, PoisonError};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use env_logger::filter::{Builder, Filter};
+use log::{LevelFilter, Log, Metadata, Record};
+
+/// 日志文件超过该大小后轮转
+pub const MAX_LOG_SIZE: u64 = 1024 * 1024;
+/// 保留的旧日志文件数量，依次命名为 `z-editor.log.1`、`z-editor.log.2` …
+const MAX_OLD_LOGS: usize = 3;
+/// 内存中保留的最近日志条数，供编辑器内查看
+const MAX_RECENT_LINES: usize = 1000;
+
+/// 内存中保留的最近日志
+static RECENT_LINES: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+/// 当前写入的日志文件路径
+static LOG_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+/// 日志记录器：按过滤规则将日志写入按大小轮转的日志文件，并在内存中保留最近的日志
+struct Logger {
+    // 日志过滤规则
+    filter: Filter,
+    // 日志文件，无法打开时为 None
+    file: Option<Mutex<LogFile>>,
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.filter.matches(record) {
+            return;
+        }
+        // 多个编辑器进程可能写入同一日志文件，以进程号区分
+        let line = format!(
+            "[{} {} {:<5} {}] {}",
+            timestamp(),
+            process::id(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+        if let Some(file) = &self.file {
+            // 写入失败时无处报告，忽略错误
+            let _ = file
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .write_line(&line);
+        }
+        let mut recent = RECENT_LINES.lock().unwrap_or_else(PoisonError::into_inner);
+        if recent.len() == MAX_RECENT_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(line);
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            let _ = file
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .file
+                .flush();
+        }
+    }
+}
+
+/// 按大小轮转的日志文件
+struct LogFile {
+    // 日志文件路径
+    path: PathBuf,
+    // 以追加模式打开的日志文件
+    file: File,
+    // 当前文件大小
+    size: u64,
+}
+
+impl LogFile {
+    // 以追加模式打开日志文件，必要时创建所在目录
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            size,
+        })
+    }
+
+    // 写入一行日志，文件超过大小上限时先轮转
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        if self.size > 0 && self.size + line.len() as u64 >= MAX_LOG_SIZE {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    // 轮转日志文件：z-editor.log.N-1 → z-editor.log.N，…，z-editor.log → z-editor.log.1，最旧的文件被覆盖
+    fn rotate(&mut self) -> io::Result<()> {
+        for index in (1..MAX_OLD_LOGS).rev() {
+            let from = old_log_path(&self.path, index);
+            if from.exists() {
+                fs::rename(from, old_log_path(&self.path, index + 1))?;
+            }
+        }
+        match fs::rename(&self.path, old_log_path(&self.path, 1)) {
+            // 其他编辑器进程已完成轮转
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            result => result?,
+        }
+        *self = Self::open(&self.path)?;
+        Ok(())
+    }
+}
+
+// 第 index 个旧日志文件的路径
+fn old_log_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{}", index));
+    PathBuf::from(name)
+}
+
+// 当前本地时间，精确到毫秒
+fn timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let seconds = now.as_secs() as libc::time_t;
+    // SAFETY: tm 为纯数据结构，全零是合法值；localtime_r 只写入传入的 tm
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&seconds, &mut tm) };
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        tm.tm_year + 1900,
+        tm.tm_mon + 1,
+        tm.tm_mday,
+        tm.tm_hour,
+        tm.tm_min,
+        tm.tm_sec,
+        now.subsec_millis()
+    )
+}
+
+/// 状态目录：`$XDG_STATE_HOME/z-editor`，未设置时为 `~/.local/state/z-editor`
+pub fn state_dir() -> Option<PathBuf> {
+    let dir = env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))?;
+    Some(dir.join("z-editor"))
+}
+
+/// 默认日志文件路径：`$XDG_STATE_HOME/z-editor/z-editor.log`，未设置时为 `~/.local/state/z-editor/z-editor.log`
+pub fn default_path() -> Option<PathBuf> {
+    Some(state_dir()?.join("z-editor.log"))
+}
+
+/// 初始化日志。
+///
+/// 指定 `level` 时使用该级别，否则按环境变量 `RUST_LOG` 过滤，均未指定时为 info。
+/// 日志文件无法打开时仅在内存中保留日志并返回错误
+pub fn init(path: Option<&Path>, level: Option<LevelFilter>) -> io::Result<()> {
+    let mut builder = Builder::new();
+    let spec = env::var("RUST_LOG").ok().filter(|spec| !spec.is_empty());
+    match (level, spec) {
+        (Some(level), _) => builder.filter_level(level),
+        (None, Some(spec)) => builder.parse(&spec),
+        (None, None) => builder.filter_level(LevelFilter::Info),
+    };
+    let filter = builder.build();
+    let (file, result) = match path.map(LogFile::open) {
+        Some(Ok(file)) => {
+            let _ = LOG_PATH.set(file.path.clone());
+            (Some(file), Ok(()))
+        }
+        Some(Err(e)) => (None, Err(e)),
+        None => (None, Ok(())),
+    };
+    log::set_max_level(filter.filter());
+    let logger = Logger {
+        filter,
+        file: file.map(Mutex::new),
+    };
+    log::set_boxed_logger(Box::new(logger))
+        .map_err(|e| io::Error::new(io::ErrorKind::AlreadyExists, e))?;
+    result
+}
+
+/// 获取当前写入的日志文件路径
+pub fn log_path() -> Option<&'static Path> {
+    LOG_PATH.get().map(PathBuf::as_path)
+}
+
+/// 获取内存中保留的最近日志，按时间先后排列
+pub fn recent_lines() -> Vec<String> {
+    let recent = RECENT_LINES.lock().unwrap_or_else(PoisonError::into_inner);
+    recent.iter().cloned().collect()
+}
@@ -1,36 +1,97 @@
-use crate::cursor_controller::CursorController;
-use crate::editor::Editor;
-use crate::editor_output::EditorOutput;
-use crate::view::EditorView;
-use env_logger::{Builder, Target};
-use log::LevelFilter;
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::process;
 
-mod cursor_controller;
-mod edit_log;
-mod editor;
-mod editor_output;
-mod status;
-mod view;
+use log::error;
+use z_editor::cli::{self, Invocation, Options};
+use z_editor::logger;
+use z_editor::replay::KeyScript;
+use z_editor::{terminal_guard, Editor, EditorError, VERSION};
 
-#[macro_use]
-extern crate log;
+fn main() {
+    // 参数错误时在进入 Raw 模式前退出
+    let options = match cli::parse(env::args().skip(1)) {
+        Ok(Invocation::Edit(options)) => options,
+        Ok(Invocation::Help) => {
+            println!("{}", cli::USAGE);
+            process::exit(0);
+        }
+        Ok(Invocation::Version) => {
+            println!("z-editor {}", VERSION);
+            process::exit(0);
+        }
+        Err(e) => {
+            eprintln!("z-editor: {}", e);
+            eprintln!("使用 z-editor --help 查看用法");
+            process::exit(2);
+        }
+    };
+    init_log(&options);
+    if let Some(script) = options.replay() {
+        replay(script, &options);
+    }
+    if let Err(e) = terminal_guard::install() {
+        exit_with_error(e.into(), None);
+    }
+    let mut editor = match Editor::new(&options) {
+        Ok(editor) => editor,
+        Err(e) => exit_with_error(e, None),
+    };
+    match panic::catch_unwind(AssertUnwindSafe(|| editor.run())) {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => {
+            let swap = editor.write_emergency_copy();
+            // 销毁编辑器以恢复终端，再输出错误信息
+            drop(editor);
+            exit_with_error(e, swap);
+        }
+        Err(_) => {
+            // 终端已由 panic hook 恢复，不再销毁编辑器，避免清屏时清除 panic 信息
+            if let Some(swap) = editor.write_emergency_copy() {
+                eprintln!("z-editor: 未保存的修改已写入 {}", swap.display());
+            }
+            process::exit(101);
+        }
+    }
+}
 
-/// 编辑器版本
-const VERSION: &str = "0.0.1";
-/// 制表符大小
-const TAB_SIZE: usize = 8;
+// 输出错误信息后退出，存在应急副本时提示其路径
+fn exit_with_error(e: EditorError, swap: Option<PathBuf>) -> ! {
+    error!("编辑器异常退出：{}", e);
+    eprintln!("z-editor: {}", e);
+    if let Some(swap) = swap {
+        eprintln!("z-editor: 未保存的修改已写入 {}", swap.display());
+    }
+    process::exit(e.exit_code());
+}
 
-fn main() -> crossterm::Result<()> {
-    init_log();
-    let mut editor = Editor::new();
-    editor.run();
-    Ok(())
+// 以按键脚本回放编辑过程并输出最终的文本内容与画面，只打开第一个文件
+fn replay(script: &Path, options: &Options) -> ! {
+    let file = options.files().first().and_then(|file| file.path());
+    let result = KeyScript::from_file(script)
+        .map_err(|e| EditorError::file(script, e))
+        .and_then(|script| script.replay(file));
+    match result {
+        Ok(replay) => {
+            print!("{}", replay);
+            process::exit(0);
+        }
+        Err(e) => exit_with_error(e, None),
+    }
 }
 
-// 初始化日志设置
-fn init_log() {
-    Builder::from_default_env()
-        .filter_level(LevelFilter::Trace)
-        .target(Target::Stderr)
-        .init();
+// 初始化日志设置，未指定日志文件时写入默认位置。日志文件无法打开时仅在内存中保留日志
+fn init_log(options: &Options) {
+    let path = options
+        .log_file()
+        .map(Path::to_path_buf)
+        .or_else(logger::default_path);
+    if let Err(e) = logger::init(path.as_deref(), options.log_level()) {
+        eprintln!("z-editor: 无法打开日志文件：{}", e);
+        // 明确指定的日志文件无法打开时视为参数错误
+        if options.log_file().is_some() {
+            process::exit(2);
+        }
+    }
 }
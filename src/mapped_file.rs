@@ -0,0 +1,151 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, RwLock};
+use std::thread;
+
+use memmap2::Mmap;
+
+/// 行索引检查点间隔，每隔该行数记录一次行起始位置
+const CHECKPOINT_INTERVAL: usize = 64;
+/// 后台建立索引时每批扫描的字节数
+const SCAN_CHUNK_SIZE: usize = 1 << 20;
+
+/// 内存映射的只读文件。
+///
+/// 打开时不读取文件内容，行索引在后台线程中逐步建立，仅在获取行内容时访问对应的文件区域。
+/// 文件在映射期间被截断（例如日志轮转）后不再访问映射，所有行均视为空行。
+/// 截断由 [`MappedFile::check_truncation`] 检查，调用方应在每次访问行内容前（例如每次刷新屏幕前）调用
+#[derive(Clone)]
+pub struct MappedFile {
+    // 被映射的文件，用于在访问映射前检查文件长度
+    file: Arc<File>,
+    // 文件映射
+    mmap: Arc<Mmap>,
+    // 行索引
+    index: Arc<LineIndex>,
+}
+
+/// 稀疏行索引
+struct LineIndex {
+    // 每隔 CHECKPOINT_INTERVAL 行的行起始位置
+    checkpoints: RwLock<Vec<usize>>,
+    // 已扫描到的换行符数量
+    line_breaks: AtomicUsize,
+    // 索引是否建立完成
+    done: AtomicBool,
+    // 是否检测到文件已被截断
+    truncated: AtomicBool,
+    // 截断是否已通过 check_truncation 报告给调用方
+    truncation_reported: AtomicBool,
+}
+
+impl MappedFile {
+    /// 映射指定文件，并在后台线程中建立行索引
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = Arc::new(File::open(path)?);
+        // Safety: 文件以只读方式映射，其他进程原地修改文件内容时仅影响显示结果。
+        // 文件被截断后访问超出文件末尾的页面会触发 SIGBUS 并直接终止进程，终端也无法恢复，
+        // 因此每次刷新前检查一次文件长度（见 `check_truncation`）。两次检查之间发生的截断仍会导致进程终止
+        let mmap = Arc::new(unsafe { Mmap::map(&*file)? });
+        let index = Arc::new(LineIndex {
+            checkpoints: RwLock::new(vec![0]),
+            line_breaks: AtomicUsize::new(0),
+            done: AtomicBool::new(false),
+            truncated: AtomicBool::new(false),
+            truncation_reported: AtomicBool::new(false),
+        });
+        let (thread_file, thread_mmap, thread_index) =
+            (Arc::clone(&file), Arc::clone(&mmap), Arc::clone(&index));
+        thread::spawn(move || thread_index.build(&thread_file, &thread_mmap));
+        Ok(Self { file, mmap, index })
+    }
+
+    /// 检查文件长度是否仍覆盖整个映射区域，仅在首次检测到截断时返回 true
+    pub fn check_truncation(&self) -> bool {
+        !is_intact(&self.file, &self.mmap, &self.index)
+            && !self.index.truncation_reported.swap(true, Ordering::AcqRel)
+    }
+
+    /// 是否已检测到文件在映射期间被截断，截断后不再读取文件内容
+    pub fn is_truncated(&self) -> bool {
+        self.index.truncated.load(Ordering::Acquire)
+    }
+
+    /// 行索引是否仍在建立中
+    pub fn is_indexing(&self) -> bool {
+        !self.index.done.load(Ordering::Acquire)
+    }
+
+    /// 获取当前已知的总行数，索引建立完成前仅包含已扫描的完整行
+    pub fn number_of_rows(&self) -> usize {
+        let line_breaks = self.index.line_breaks.load(Ordering::Acquire);
+        let unterminated = !self.is_indexing()
+            && !self.is_truncated()
+            && self.mmap.last().is_some_and(|&b| b != b'\n');
+        line_breaks + usize::from(unterminated)
+    }
+
+    /// 获取指定行不包含换行符的内容，无效的 UTF-8 字节以替换字符显示，文件已被截断时返回空行
+    pub fn row(&self, i: usize) -> Cow<'_, str> {
+        if self.is_truncated() {
+            return Cow::Borrowed("");
+        }
+        let checkpoint = self.index.checkpoints.read().unwrap()[i / CHECKPOINT_INTERVAL];
+        let mut start = checkpoint;
+        for _ in 0..i % CHECKPOINT_INTERVAL {
+            start += Self::find_line_break(&self.mmap[start..]).map_or(0, |p| p + 1);
+        }
+        let rest = &self.mmap[start..];
+        let mut line = &rest[..Self::find_line_break(rest).unwrap_or(rest.len())];
+        if let Some(stripped) = line.strip_suffix(b"\r") {
+            line = stripped;
+        }
+        String::from_utf8_lossy(line)
+    }
+
+    fn find_line_break(bytes: &[u8]) -> Option<usize> {
+        bytes.iter().position(|&b| b == b'\n')
+    }
+}
+
+impl LineIndex {
+    // 扫描文件内容，记录换行符数量与检查点，文件被截断时停止扫描
+    fn build(&self, file: &File, bytes: &[u8]) {
+        let mut line_breaks = 0;
+        for (chunk_index, chunk) in bytes.chunks(SCAN_CHUNK_SIZE).enumerate() {
+            if !is_intact(file, bytes, self) {
+                break;
+            }
+            let chunk_start = chunk_index * SCAN_CHUNK_SIZE;
+            let mut checkpoints = Vec::new();
+            for (i, _) in chunk.iter().enumerate().filter(|(_, &b)| b == b'\n') {
+                line_breaks += 1;
+                if line_breaks % CHECKPOINT_INTERVAL == 0 {
+                    checkpoints.push(chunk_start + i + 1);
+                }
+            }
+            self.checkpoints.write().unwrap().extend(checkpoints);
+            self.line_breaks.store(line_breaks, Ordering::Release);
+        }
+        self.done.store(true, Ordering::Release);
+        info!("文件行索引建立完成，总行数：{}", line_breaks);
+    }
+}
+
+// 文件长度是否仍覆盖整个映射区域，检测到截断后记录状态，之后不再检查
+fn is_intact(file: &File, mmap: &[u8], index: &LineIndex) -> bool {
+    if index.truncated.load(Ordering::Acquire) {
+        return false;
+    }
+    let intact = file
+        .metadata()
+        .is_ok_and(|metadata| metadata.len() >= mmap.len() as u64);
+    if !intact {
+        warn!("映射的文件已被截断，停止读取文件内容");
+        index.truncated.store(true, Ordering::Release);
+    }
+    intact
+}
@@ -0,0 +1,293 @@
+use std::cmp;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::buffer::TextBuffer;
+
+/// 可匹配的括号对
+const BRACKET_PAIRS: [(char, char); 7] = [
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('（', '）'),
+    ('【', '】'),
+    ('「', '」'),
+    ('《', '》'),
+];
+/// 查找匹配括号时最多扫描的行数，避免在超大文件中长时间扫描
+const MAX_BRACKET_SEARCH_ROWS: usize = 10_000;
+
+/// 文本缓冲区中的位置。
+///
+/// `x` 为行内不包含换行符的字节偏移量。行号等于总行数时表示文本末尾之后的空行，此时 `x` 为 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    /// 行号
+    pub row: usize,
+    /// 行内字节偏移量
+    pub x: usize,
+}
+
+impl Position {
+    /// 创建位置
+    pub fn new(row: usize, x: usize) -> Self {
+        Self { row, x }
+    }
+}
+
+/// 光标移动：均按原内容中的位置计算，不依赖渲染。
+///
+/// 行内移动按字形簇进行，不会停留在宽字符或组合字符中间。
+/// 文件在外部被修改后，位置可能超出行末或位于字符中间，此时先取其之前最近的字符边界
+impl TextBuffer {
+    /// 获取指定行渲染后所占列数，文本末尾之后的行为 0
+    pub fn row_width(&self, row: usize) -> usize {
+        if row < self.number_of_rows() {
+            self.layout().width(&self.row(row))
+        } else {
+            0
+        }
+    }
+
+    /// 获取位置对应的渲染列
+    pub fn render_x(&self, position: Position) -> usize {
+        if position.row < self.number_of_rows() {
+            self.layout().render_x(&self.row(position.row), position.x)
+        } else {
+            0
+        }
+    }
+
+    /// 获取指定行指定渲染列处的位置，行号超出文本范围时取文本末尾之后的行，渲染列超出行末时取行末
+    pub fn position_at_render_x(&self, row: usize, render_x: usize) -> Position {
+        let row = cmp::min(row, self.number_of_rows());
+        if row == self.number_of_rows() {
+            return Position::new(row, 0);
+        }
+        Position::new(row, self.layout().raw_x(&self.row(row), render_x))
+    }
+
+    /// 获取指定行第 `column` 个字符处的位置，列位于字形簇中间时取字形簇起始位置，超出行末时取行末
+    pub fn position_at_char(&self, row: usize, column: usize) -> Position {
+        let row = cmp::min(row, self.number_of_rows());
+        if row == self.number_of_rows() {
+            return Position::new(row, 0);
+        }
+        let raw_content = self.row(row);
+        let mut chars = 0;
+        for (i, grapheme) in raw_content.grapheme_indices(true) {
+            chars += grapheme.chars().count();
+            if chars > column {
+                return Position::new(row, i);
+            }
+        }
+        Position::new(row, raw_content.len())
+    }
+
+    /// 上一个字形簇的位置，位于行首时为上一行行末，位于文本开头时不动
+    pub fn prev_grapheme(&self, position: Position) -> Position {
+        let Position { row, x } = position;
+        if x > 0 {
+            let raw_content = self.row(row);
+            let x = raw_content[..raw_content.floor_char_boundary(x)]
+                .grapheme_indices(true)
+                .next_back()
+                .map_or(0, |(i, _)| i);
+            Position::new(row, x)
+        } else if row > 0 {
+            self.line_end(Position::new(row - 1, 0))
+        } else {
+            position
+        }
+    }
+
+    /// 下一个字形簇的位置，位于行末时为下一行行首，位于文本末尾之后时不动
+    pub fn next_grapheme(&self, position: Position) -> Position {
+        let Position { row, x } = position;
+        if row >= self.number_of_rows() {
+            return position;
+        }
+        let raw_content = self.row(row);
+        let x = raw_content.floor_char_boundary(x);
+        match raw_content[x..].graphemes(true).next() {
+            Some(grapheme) => Position::new(row, x + grapheme.len()),
+            None => Position::new(row + 1, 0),
+        }
+    }
+
+    /// 行首位置
+    pub fn line_start(&self, position: Position) -> Position {
+        Position::new(position.row, 0)
+    }
+
+    /// 行末位置，位于文本末尾之后时不动
+    pub fn line_end(&self, position: Position) -> Position {
+        if position.row >= self.number_of_rows() {
+            return position;
+        }
+        Position::new(position.row, self.row(position.row).len())
+    }
+
+    // 单词边界切分出的片段是否只包含空白
+    fn is_blank(text: &str) -> bool {
+        text.chars().all(char::is_whitespace)
+    }
+
+    /// 下一个单词开头的位置，位于行末时为下一行行首。
+    ///
+    /// 按 Unicode 单词边界划分，中日韩文字逐字停留，标点单独视为一个单词
+    pub fn word_forward(&self, position: Position) -> Position {
+        let Position { row, x } = position;
+        if row >= self.number_of_rows() {
+            return position;
+        }
+        let raw_content = self.row(row);
+        let x = raw_content.floor_char_boundary(x);
+        if x >= raw_content.len() {
+            if row + 1 < self.number_of_rows() {
+                return Position::new(row + 1, 0);
+            }
+            return position;
+        }
+        let target = raw_content[x..]
+            .split_word_bound_indices()
+            .skip(1)
+            .find(|(_, word)| !Self::is_blank(word))
+            .map_or(raw_content.len(), |(i, _)| x + i);
+        Position::new(row, target)
+    }
+
+    /// 当前或上一个单词开头的位置，位于行首时为上一行行末
+    pub fn word_backward(&self, position: Position) -> Position {
+        let Position { row, x } = position;
+        if x == 0 || row >= self.number_of_rows() {
+            if row > 0 {
+                return self.line_end(Position::new(row - 1, 0));
+            }
+            return position;
+        }
+        let raw_content = self.row(row);
+        let target = raw_content[..raw_content.floor_char_boundary(x)]
+            .split_word_bound_indices()
+            .rev()
+            .find(|(_, word)| !Self::is_blank(word))
+            .map_or(0, |(i, _)| i);
+        Position::new(row, target)
+    }
+
+    // 行是否为空白行，空白行分隔段落
+    fn is_blank_row(&self, row: usize) -> bool {
+        Self::is_blank(&self.row(row))
+    }
+
+    /// 下一个段落之后的空白行行首，之后没有空白行时为文本末尾
+    pub fn paragraph_forward(&self, position: Position) -> Position {
+        let number_of_rows = self.number_of_rows();
+        let mut row = position.row;
+        while row < number_of_rows && self.is_blank_row(row) {
+            row += 1;
+        }
+        while row < number_of_rows && !self.is_blank_row(row) {
+            row += 1;
+        }
+        if row < number_of_rows {
+            Position::new(row, 0)
+        } else {
+            self.buffer_end()
+        }
+    }
+
+    /// 上一个段落之前的空白行行首，之前没有空白行时为文本开头
+    pub fn paragraph_backward(&self, position: Position) -> Position {
+        let mut row = cmp::min(position.row, self.number_of_rows().saturating_sub(1));
+        if self.number_of_rows() > 0 {
+            while row > 0 && self.is_blank_row(row) {
+                row -= 1;
+            }
+            while row > 0 && !self.is_blank_row(row) {
+                row -= 1;
+            }
+        }
+        Position::new(row, 0)
+    }
+
+    /// 文本开头
+    pub fn buffer_start(&self) -> Position {
+        Position::new(0, 0)
+    }
+
+    /// 文本末尾，即最后一行行末
+    pub fn buffer_end(&self) -> Position {
+        let row = self.number_of_rows().saturating_sub(1);
+        self.line_end(Position::new(row, 0))
+    }
+
+    /// 位置处括号的匹配括号位置，位置处不是括号或找不到匹配括号时返回 None
+    pub fn matching_bracket(&self, position: Position) -> Option<Position> {
+        let Position { row, x } = position;
+        if row >= self.number_of_rows() {
+            return None;
+        }
+        let raw_content = self.row(row);
+        let x = raw_content.floor_char_boundary(x);
+        let c = raw_content[x..].chars().next()?;
+        let &(open, close) = BRACKET_PAIRS
+            .iter()
+            .find(|&&(open, close)| c == open || c == close)?;
+        if c == open {
+            self.find_closing_bracket(Position::new(row, x + c.len_utf8()), (open, close))
+        } else {
+            self.find_opening_bracket(Position::new(row, x), (open, close))
+        }
+    }
+
+    // 从指定位置向后查找匹配的右括号
+    fn find_closing_bracket(
+        &self,
+        start: Position,
+        (open, close): (char, char),
+    ) -> Option<Position> {
+        let end_row = cmp::min(self.number_of_rows(), start.row + MAX_BRACKET_SEARCH_ROWS);
+        let mut depth = 0;
+        for current in start.row..end_row {
+            let raw_content = self.row(current);
+            let from = if current == start.row { start.x } else { 0 };
+            for (i, c) in raw_content[from..].char_indices() {
+                if c == open {
+                    depth += 1;
+                } else if c == close {
+                    if depth == 0 {
+                        return Some(Position::new(current, from + i));
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+        None
+    }
+
+    // 从指定位置向前查找匹配的左括号
+    fn find_opening_bracket(&self, end: Position, (open, close): (char, char)) -> Option<Position> {
+        let start_row = (end.row + 1).saturating_sub(MAX_BRACKET_SEARCH_ROWS);
+        let mut depth = 0;
+        for current in (start_row..=end.row).rev() {
+            let raw_content = self.row(current);
+            let to = if current == end.row {
+                end.x
+            } else {
+                raw_content.len()
+            };
+            for (i, c) in raw_content[..to].char_indices().rev() {
+                if c == close {
+                    depth += 1;
+                } else if c == open {
+                    if depth == 0 {
+                        return Some(Position::new(current, i));
+                    }
+                    depth -= 1;
+                }
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,75 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+/// 提示输入类型，决定输入完成后执行的操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PromptKind {
+    /// 以指定编码重新打开文件
+    ReopenWithEncoding,
+    /// 转换文件保存时使用的编码
+    ConvertEncoding,
+    /// 转换换行符风格
+    ConvertLineEnding,
+    /// 处理过期的交换文件
+    RecoverSwap,
+    /// 处理在外部被修改的文件
+    ExternalChange,
+    /// 将未命名的内容另存为指定文件
+    SaveAs,
+    /// 跳转到指定行列
+    GotoLine,
+}
+
+/// 提示输入的处理结果
+pub enum PromptResult {
+    /// 仍在输入中
+    Pending,
+    /// 输入完成
+    Done(PromptKind, String),
+    /// 取消输入
+    Cancelled(PromptKind),
+}
+
+/// 消息栏中的提示输入
+pub struct Prompt {
+    // 提示文本
+    label: String,
+    // 已输入内容
+    input: String,
+    // 提示输入类型
+    kind: PromptKind,
+}
+
+impl Prompt {
+    /// 创建提示输入
+    pub fn new(label: &str, kind: PromptKind) -> Self {
+        Self {
+            label: label.to_string(),
+            input: String::new(),
+            kind,
+        }
+    }
+
+    /// 获取消息栏中显示的内容
+    pub fn message(&self) -> String {
+        format!("{}{}", self.label, self.input)
+    }
+
+    /// 处理按键事件：Enter 完成输入，Esc 取消输入
+    pub fn process_key(&mut self, event: KeyEvent) -> PromptResult {
+        match (event.code, event.modifiers) {
+            (KeyCode::Enter, _) => PromptResult::Done(self.kind, self.input.trim().to_string()),
+            (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                PromptResult::Cancelled(self.kind)
+            }
+            (KeyCode::Backspace, _) => {
+                self.input.pop();
+                PromptResult::Pending
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                self.input.push(c);
+                PromptResult::Pending
+            }
+            _ => PromptResult::Pending,
+        }
+    }
+}
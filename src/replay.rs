@@ -0,0 +1,202 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use crate::backend::HeadlessBackend;
+use crate::editor::Editor;
+use crate::error::Result;
+
+/// 默认的虚拟窗口大小
+const DEFAULT_SIZE: (usize, usize) = (80, 24);
+
+/// 按键脚本。
+///
+/// 每行一条指令，空行与以 `#` 开头的行被忽略：
+///
+/// ```text
+/// # 虚拟窗口大小，默认为 80x24
+/// size 40x10
+/// # 逐字符输入文本
+/// type hello 世界
+/// # 按键名称，可带 Ctrl-、Alt-、Shift- 修饰，*N 表示重复 N 次
+/// Enter
+/// Left*3
+/// Ctrl-S
+/// ```
+pub struct KeyScript {
+    // 虚拟窗口大小 (列数, 行数)
+    size: (usize, usize),
+    // 按顺序输入的按键
+    keys: Vec<KeyEvent>,
+}
+
+impl KeyScript {
+    /// 解析按键脚本，出错时返回带行号的错误信息
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut script = Self {
+            size: DEFAULT_SIZE,
+            keys: Vec::new(),
+        };
+        for (number, line) in text.lines().enumerate() {
+            script
+                .parse_line(line)
+                .map_err(|e| format!("第 {} 行：{}", number + 1, e))?;
+        }
+        Ok(script)
+    }
+
+    /// 读取并解析按键脚本文件
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        Self::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// 在虚拟窗口中打开文件并依次输入脚本中的按键，未指定文件时打开空缓冲区。
+    ///
+    /// 编辑器退出后不再处理剩余按键。脚本中的保存操作会写入文件
+    pub fn replay(&self, file: Option<&Path>) -> Result<Replay> {
+        let backend = HeadlessBackend::new(self.size.0, self.size.1);
+        let mut editor = Editor::open(file, Box::new(backend.clone()))?;
+        editor.refresh_screen()?;
+        let mut exited = false;
+        for key in &self.keys {
+            if editor.handle_event(Event::Key(*key))? {
+                exited = true;
+                break;
+            }
+            editor.refresh_screen()?;
+        }
+        Ok(Replay {
+            // 以内存映射方式只读打开的超大文件没有完整的文本内容
+            text: editor.text().unwrap_or_default(),
+            screen: backend.snapshot(),
+            exited,
+        })
+    }
+
+    // 解析一行指令
+    fn parse_line(&mut self, line: &str) -> std::result::Result<(), String> {
+        if let Some(text) = line.strip_prefix("type ") {
+            let keys = text
+                .chars()
+                .map(|c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            self.keys.extend(keys);
+            return Ok(());
+        }
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+        if let Some(size) = line.strip_prefix("size ") {
+            self.size = Self::parse_size(size.trim())
+                .ok_or_else(|| format!("无法识别的窗口大小：{}", size))?;
+            return Ok(());
+        }
+        let (name, times) = match line.rsplit_once('*') {
+            Some((name, times)) if !name.is_empty() => {
+                let times = times
+                    .parse()
+                    .map_err(|_| format!("无法识别的重复次数：{}", times))?;
+                (name, times)
+            }
+            _ => (line, 1),
+        };
+        let key = Self::parse_key(name).ok_or_else(|| format!("无法识别的按键：{}", name))?;
+        self.keys.extend(std::iter::repeat_n(key, times));
+        Ok(())
+    }
+
+    // 解析窗口大小，格式为 列数x行数
+    fn parse_size(size: &str) -> Option<(usize, usize)> {
+        let (columns, rows) = size.split_once('x')?;
+        Some((columns.parse().ok()?, rows.parse().ok()?))
+    }
+
+    // 解析按键名称
+    fn parse_key(name: &str) -> Option<KeyEvent> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = name;
+        loop {
+            if let Some(key) = rest.strip_prefix("Ctrl-") {
+                modifiers |= KeyModifiers::CONTROL;
+                rest = key;
+            } else if let Some(key) = rest.strip_prefix("Alt-") {
+                modifiers |= KeyModifiers::ALT;
+                rest = key;
+            } else if let Some(key) = rest.strip_prefix("Shift-") {
+                modifiers |= KeyModifiers::SHIFT;
+                rest = key;
+            } else {
+                break;
+            }
+        }
+        let code = match rest {
+            "Enter" => KeyCode::Enter,
+            "Esc" => KeyCode::Esc,
+            "Tab" => KeyCode::Tab,
+            "BackTab" => KeyCode::BackTab,
+            "Backspace" => KeyCode::Backspace,
+            "Delete" => KeyCode::Delete,
+            "Insert" => KeyCode::Insert,
+            "Left" => KeyCode::Left,
+            "Right" => KeyCode::Right,
+            "Up" => KeyCode::Up,
+            "Down" => KeyCode::Down,
+            "Home" => KeyCode::Home,
+            "End" => KeyCode::End,
+            "PageUp" => KeyCode::PageUp,
+            "PageDown" => KeyCode::PageDown,
+            "Space" => KeyCode::Char(' '),
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    (Some('F'), Some(_)) => KeyCode::F(rest[1..].parse().ok()?),
+                    _ => return None,
+                }
+            }
+        };
+        Some(KeyEvent::new(code, modifiers))
+    }
+}
+
+/// 按键脚本的回放结果
+pub struct Replay {
+    // 最终的文本内容
+    text: String,
+    // 最终的画面快照
+    screen: String,
+    // 编辑器是否已退出
+    exited: bool,
+}
+
+impl Replay {
+    /// 获取最终的文本内容
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// 获取最终的画面快照
+    pub fn screen(&self) -> &str {
+        &self.screen
+    }
+}
+
+impl fmt::Display for Replay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "--- buffer")?;
+        write!(f, "{}", self.text)?;
+        if !self.text.is_empty() && !self.text.ends_with('\n') {
+            writeln!(f)?;
+        }
+        writeln!(
+            f,
+            "--- screen{}",
+            if self.exited { " (exited)" } else { "" }
+        )?;
+        write!(f, "{}", self.screen)
+    }
+}
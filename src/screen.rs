@@ -0,0 +1,245 @@
+use std::io;
+use std::io::Write;
+
+use crossterm::style::Attribute;
+use crossterm::{cursor, queue, style, terminal};
+use unicode_segmentation::UnicodeSegmentation;
+
+use crate::unicode::grapheme_width;
+
+/// 屏幕单元格
+#[derive(Clone, Default, PartialEq, Eq)]
+struct Cell {
+    // 单元格内容，空字符串表示空白
+    symbol: String,
+    // 是否反色显示
+    reverse: bool,
+    // 是否为宽字符占用的后续单元格，该单元格随宽字符一同输出
+    continuation: bool,
+}
+
+/// 屏幕帧，按单元格保存一帧完整的屏幕内容
+pub struct Frame {
+    // 列数
+    width: usize,
+    // 行数
+    height: usize,
+    // 按行排列的单元格
+    cells: Vec<Cell>,
+    // 光标位置 (列, 行)，None 时隐藏光标
+    cursor: Option<(usize, usize)>,
+}
+
+impl Frame {
+    /// 创建指定大小的空白帧
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            cursor: None,
+        }
+    }
+
+    /// 从指定位置开始写入文本，返回写入后所在的列。超出行宽的内容被截断，
+    /// 被行尾截断的宽字符以空格填充，不占列的控制字符被忽略
+    pub fn put_str(&mut self, x: usize, y: usize, text: &str, reverse: bool) -> usize {
+        if y >= self.height {
+            return x;
+        }
+        let mut x = x;
+        for grapheme in text.graphemes(true) {
+            let width = grapheme_width(grapheme);
+            if width == 0 {
+                continue;
+            }
+            if x + width > self.width {
+                while x < self.width {
+                    self.set_cell(x, y, Cell::blank(reverse));
+                    x += 1;
+                }
+                break;
+            }
+            self.set_cell(
+                x,
+                y,
+                Cell {
+                    symbol: grapheme.to_string(),
+                    reverse,
+                    continuation: false,
+                },
+            );
+            for i in 1..width {
+                self.set_cell(
+                    x + i,
+                    y,
+                    Cell {
+                        continuation: true,
+                        ..Cell::blank(reverse)
+                    },
+                );
+            }
+            x += width;
+        }
+        x
+    }
+
+    /// 将指定行从指定列开始的剩余部分填充为空白
+    pub fn fill(&mut self, x: usize, y: usize, reverse: bool) {
+        for x in x..self.width {
+            self.set_cell(x, y, Cell::blank(reverse));
+        }
+    }
+
+    /// 设置光标位置
+    pub fn set_cursor(&mut self, x: usize, y: usize) {
+        self.cursor = Some((x, y));
+    }
+
+    /// 获取指定行的文本，省略行尾空白
+    pub fn row_text(&self, y: usize) -> String {
+        let text: String = (0..self.width)
+            .map(|x| self.cell(x, y))
+            .filter(|cell| !cell.continuation)
+            .map(|cell| match cell.symbol.as_str() {
+                "" => " ",
+                symbol => symbol,
+            })
+            .collect();
+        text.trim_end().to_string()
+    }
+
+    /// 获取光标位置 (列, 行)，None 表示隐藏光标
+    pub fn cursor(&self) -> Option<(usize, usize)> {
+        self.cursor
+    }
+
+    // 设置单元格，覆盖宽字符的一部分时将该宽字符的其余部分置为空白
+    fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
+        let index = y * self.width + x;
+        if self.cells[index].continuation && !cell.continuation {
+            let mut i = index;
+            while i > y * self.width && self.cells[i].continuation {
+                i -= 1;
+                let reverse = self.cells[i].reverse;
+                self.cells[i] = Cell::blank(reverse);
+            }
+        }
+        let mut i = index + 1;
+        while i < (y + 1) * self.width && self.cells[i].continuation {
+            let reverse = self.cells[i].reverse;
+            self.cells[i] = Cell::blank(reverse);
+            i += 1;
+        }
+        self.cells[index] = cell;
+    }
+
+    // 获取单元格
+    fn cell(&self, x: usize, y: usize) -> &Cell {
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl Cell {
+    // 创建空白单元格
+    fn blank(reverse: bool) -> Self {
+        Self {
+            reverse,
+            ..Self::default()
+        }
+    }
+}
+
+/// 屏幕。
+///
+/// 保存上一次输出的帧作为影子屏幕，每次仅输出与之不同的单元格；
+/// 首次输出或窗口大小变化时清屏后完整输出
+pub struct Screen {
+    // 上一次输出的帧
+    previous: Option<Frame>,
+}
+
+impl Default for Screen {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Screen {
+    /// 创建屏幕
+    pub fn new() -> Self {
+        Self { previous: None }
+    }
+
+    /// 使影子屏幕失效，下一次输出时完整重绘
+    pub fn invalidate(&mut self) {
+        self.previous = None;
+    }
+
+    /// 输出新帧与上一帧之间的差异，返回是否输出了内容。内容与光标均未变化时不输出任何内容
+    pub fn draw(&mut self, frame: Frame, output: &mut impl Write) -> io::Result<bool> {
+        let mut changes = Vec::new();
+        let previous = match self.previous.take() {
+            Some(previous) if previous.width == frame.width && previous.height == frame.height => {
+                previous
+            }
+            _ => {
+                // 清屏后的屏幕等同于空白帧
+                queue!(changes, terminal::Clear(terminal::ClearType::All))?;
+                Frame::new(frame.width, frame.height)
+            }
+        };
+        Self::write_changes(&previous, &frame, &mut changes)?;
+        let cursor_changed = previous.cursor != frame.cursor;
+        self.previous = Some(frame);
+        if changes.is_empty() && !cursor_changed {
+            return Ok(false);
+        }
+        if !changes.is_empty() {
+            queue!(output, cursor::Hide)?;
+            output.write_all(&changes)?;
+        }
+        match self.previous.as_ref().and_then(|frame| frame.cursor) {
+            Some((x, y)) => queue!(output, cursor::MoveTo(x as u16, y as u16), cursor::Show)?,
+            None => queue!(output, cursor::Hide)?,
+        }
+        Ok(true)
+    }
+
+    // 输出两帧之间发生变化的单元格
+    fn write_changes(previous: &Frame, frame: &Frame, output: &mut Vec<u8>) -> io::Result<()> {
+        let mut reverse = false;
+        // 终端光标的当前位置，未知时为 None
+        let mut position = None;
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let cell = frame.cell(x, y);
+                if cell == previous.cell(x, y) || cell.continuation {
+                    continue;
+                }
+                if position != Some((x, y)) {
+                    queue!(output, cursor::MoveTo(x as u16, y as u16))?;
+                }
+                if cell.reverse != reverse {
+                    reverse = cell.reverse;
+                    let attribute = if reverse {
+                        Attribute::Reverse
+                    } else {
+                        Attribute::NoReverse
+                    };
+                    queue!(output, style::SetAttribute(attribute))?;
+                }
+                let symbol = match cell.symbol.as_str() {
+                    "" => " ",
+                    symbol => symbol,
+                };
+                output.write_all(symbol.as_bytes())?;
+                position = Some((x + grapheme_width(symbol), y));
+            }
+        }
+        if reverse {
+            queue!(output, style::SetAttribute(Attribute::Reset))?;
+        }
+        Ok(())
+    }
+}
@@ -1,41 +1,86 @@
+use crate::encoding::FileEncoding;
 use crate::status::Status::Saved;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// 状态信息
 pub struct StatusInfo {
     // 文件名
     file_name: Option<PathBuf>,
-    // 当前行数
-    lines: usize,
+    // 未指定文件名时显示的名称
+    default_name: &'static str,
     // 状态
     status: Status,
+    // 文件编码
+    encoding: FileEncoding,
     // 显示信息
     message: String,
 }
 
 impl StatusInfo {
     /// 创建状态信息
-    pub fn new(file_name: Option<PathBuf>, lines: usize, initial_message: String) -> Self {
+    pub fn new(file_name: Option<PathBuf>, initial_message: String) -> Self {
         Self {
             file_name,
-            lines,
+            default_name: "[No Name]",
             status: Saved,
+            encoding: FileEncoding::utf8(),
             message: initial_message,
         }
     }
 
-    /// 获取文件名。如果不存在，则返回默认名称：**[No Name]**
+    /// 获取文件名。如果不存在，则返回默认名称，默认为 **[No Name]**
     pub fn file_name_or_default(&self) -> &str {
         self.file_name
             .as_ref()
             .and_then(|path| path.file_name())
             .and_then(|name| name.to_str())
-            .unwrap_or("[No Name]")
+            .unwrap_or(self.default_name)
     }
 
+    /// 获取文件路径
+    pub fn get_file_name(&self) -> Option<&Path> {
+        self.file_name.as_deref()
+    }
+
+    /// 设置文件路径，另存为后使用
+    pub fn set_file_name(&mut self, file_name: PathBuf) {
+        self.file_name = Some(file_name);
+    }
+
+    /// 设置未指定文件名时显示的名称，例如读取自标准输入的内容显示为 **[stdin]**
+    pub fn set_default_name(&mut self, default_name: &'static str) {
+        self.default_name = default_name;
+    }
+
+    /// 获取消息栏中显示的信息
     pub fn get_message(&self) -> &str {
         self.message.as_str()
     }
+
+    /// 设置显示信息
+    pub fn set_message(&mut self, message: String) {
+        self.message = message;
+    }
+
+    /// 获取状态
+    pub fn get_status(&self) -> &Status {
+        &self.status
+    }
+
+    /// 设置状态
+    pub fn set_status(&mut self, status: Status) {
+        self.status = status;
+    }
+
+    /// 获取文件编码
+    pub fn get_encoding(&self) -> &FileEncoding {
+        &self.encoding
+    }
+
+    /// 设置文件编码
+    pub fn set_encoding(&mut self, encoding: FileEncoding) {
+        self.encoding = encoding;
+    }
 }
 
 /// 状态枚举
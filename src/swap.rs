@@ -0,0 +1,210 @@
+use std::fs;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{BufWriter, Write};
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// 交换文件首行标识
+const MAGIC: &str = "z-editor swap";
+/// 交换文件权限，交换文件保存了未保存的内容，仅允许所有者读写
+const SWAP_MODE: u32 = 0o600;
+
+/// 交换文件状态
+pub enum SwapStatus {
+    /// 交换文件不存在
+    Missing,
+    /// 另一个正在运行的 z-editor 进程正在编辑该文件
+    Running(u32),
+    /// 交换文件来自已退出的会话，可能保存了未保存的修改
+    Stale,
+}
+
+/// 交换文件。
+///
+/// 位于被编辑文件旁的 `.<文件名>.swp`，未命名的缓冲区则位于状态目录中，文件名带有进程号。
+/// 由文件头与文件内容组成。文件头记录写入者的进程号与进程启动时间，以空行结束；文件内容与保存时写入磁盘的字节相同
+#[derive(Clone)]
+pub struct SwapFile {
+    // 交换文件路径
+    path: PathBuf,
+}
+
+impl SwapFile {
+    /// 获取指定文件对应的交换文件。
+    ///
+    /// 未指定文件名时位于状态目录（无法确定时为当前目录），文件名带有进程号，避免多个未命名的会话相互冲突
+    pub fn for_file(file: Option<&Path>) -> Self {
+        let path = match file {
+            Some(file) => {
+                let name = file
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .unwrap_or("noname");
+                let dir = file.parent().unwrap_or(Path::new(""));
+                dir.join(format!(".{}.swp", name))
+            }
+            None => crate::logger::state_dir()
+                .unwrap_or_default()
+                .join(format!(".noname.{}.swp", process::id())),
+        };
+        Self { path }
+    }
+
+    /// 获取备用交换文件：与原交换文件位于同一目录，文件名带有进程号。
+    ///
+    /// 原交换文件正被其他进程使用，或用户选择保留过期的交换文件时使用
+    pub fn alternate(&self) -> Self {
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let stem = name.strip_suffix(".swp").unwrap_or(&name);
+        Self {
+            path: self
+                .path
+                .with_file_name(format!("{}.{}.swp", stem, process::id())),
+        }
+    }
+
+    /// 获取交换文件路径
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// 检查交换文件状态，文件头无法识别时视为过期的交换文件
+    pub fn status(&self) -> SwapStatus {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return SwapStatus::Missing,
+            Err(e) => {
+                warn!("读取交换文件失败：{:?}，{}", self.path, e);
+                return SwapStatus::Stale;
+            }
+        };
+        match Self::parse_header(&bytes) {
+            Some(header) if header.pid != process::id() && header.is_process_alive() => {
+                SwapStatus::Running(header.pid)
+            }
+            _ => SwapStatus::Stale,
+        }
+    }
+
+    /// 写入交换文件，`write_content` 负责写入文件内容并返回其字节数。
+    ///
+    /// 先写入同一目录中的临时文件再重命名，写入中途崩溃时不会留下不完整的交换文件
+    pub fn write(
+        &self,
+        write_content: impl FnOnce(&mut dyn Write) -> io::Result<usize>,
+    ) -> io::Result<usize> {
+        if let Some(dir) = self.path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_path = self
+            .path
+            .with_file_name(format!("{}.{}.tmp", name, process::id()));
+        let result = Self::write_new(&temp_path, write_content)
+            .and_then(|len| fs::rename(&temp_path, &self.path).map(|_| len));
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+        result
+    }
+
+    // 创建仅所有者可读写的新文件，写入文件头与文件内容
+    fn write_new(
+        path: &Path,
+        write_content: impl FnOnce(&mut dyn Write) -> io::Result<usize>,
+    ) -> io::Result<usize> {
+        let file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(SWAP_MODE)
+            .open(path)?;
+        let mut writer = BufWriter::new(file);
+        writeln!(writer, "{}\npid: {}", MAGIC, process::id())?;
+        if let Some(start_time) = process_start_time(process::id()) {
+            writeln!(writer, "start: {}", start_time)?;
+        }
+        writeln!(writer)?;
+        let len = write_content(&mut writer)?;
+        writer
+            .into_inner()
+            .map_err(|e| e.into_error())?
+            .sync_all()?;
+        Ok(len)
+    }
+
+    /// 读取交换文件中保存的文件内容
+    pub fn read_content(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = fs::read(&self.path)?;
+        let header = Self::parse_header(&bytes)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "无法识别的交换文件格式"))?;
+        bytes.drain(..header.len);
+        Ok(bytes)
+    }
+
+    /// 删除交换文件，文件不存在时忽略
+    pub fn remove(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    // 解析文件头，启动时间一行可以省略
+    fn parse_header(bytes: &[u8]) -> Option<SwapHeader> {
+        let header_end = bytes.windows(2).position(|w| w == b"\n\n")? + 2;
+        let header = std::str::from_utf8(&bytes[..header_end]).ok()?;
+        let mut lines = header.lines();
+        if lines.next()? != MAGIC {
+            return None;
+        }
+        let pid = lines.next()?.strip_prefix("pid: ")?.parse().ok()?;
+        let start_time = match lines.next() {
+            Some(line) if !line.is_empty() => Some(line.strip_prefix("start: ")?.parse().ok()?),
+            _ => None,
+        };
+        Some(SwapHeader {
+            pid,
+            start_time,
+            len: header_end,
+        })
+    }
+}
+
+/// 交换文件头
+struct SwapHeader {
+    // 写入者的进程号
+    pid: u32,
+    // 写入者的进程启动时间
+    start_time: Option<u64>,
+    // 文件头长度
+    len: usize,
+}
+
+impl SwapHeader {
+    // 写入者是否仍在运行。进程号被其他进程复用时启动时间不同，视为已退出
+    fn is_process_alive(&self) -> bool {
+        // 信号 0 不会发送信号，仅检查进程是否存在；无权限时进程同样存在
+        let result = unsafe { libc::kill(self.pid as libc::pid_t, 0) };
+        let exists = result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM);
+        exists
+            && match (self.start_time, process_start_time(self.pid)) {
+                (Some(recorded), Some(current)) => recorded == current,
+                _ => true,
+            }
+    }
+}
+
+// 读取进程启动时间（系统启动后经过的时钟周期数），无法读取时返回 None
+fn process_start_time(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // 进程名可能包含空格与括号，从最后一个右括号之后开始解析，启动时间为第 22 个字段
+    stat.rsplit_once(')')?
+        .1
+        .split_whitespace()
+        .nth(19)?
+        .parse()
+        .ok()
+}
@@ -0,0 +1,263 @@
+use std::fs::OpenOptions;
+use std::io;
+use std::io::{stdout, IsTerminal, Read, Write};
+use std::os::unix::io::AsRawFd;
+use std::panic;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossterm::terminal::ClearType;
+use crossterm::{cursor, execute, terminal};
+use signal_hook::consts::{SIGCONT, SIGHUP, SIGTERM, SIGTSTP};
+use signal_hook::iterator::Signals;
+
+/// 收到终止信号后等待主循环退出的最长时间
+const SIGNAL_EXIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 等待终端响应查询的最长时间
+const QUERY_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// 收到的终止信号，0 表示尚未收到
+static SIGNAL: AtomicUsize = AtomicUsize::new(0);
+/// 是否收到了外部发送的 SIGTSTP，尚未由编辑器主循环挂起
+static SUSPEND_REQUESTED: AtomicBool = AtomicBool::new(false);
+/// 是否收到了 SIGCONT，尚未由编辑器主循环重新进入编辑状态
+static CONTINUED: AtomicBool = AtomicBool::new(false);
+/// 是否已进入备用屏幕
+static ALTERNATE_SCREEN: AtomicBool = AtomicBool::new(false);
+/// 编辑器登记的应急副本写入函数，没有未保存的修改时为 None
+static EMERGENCY_COPY: Mutex<Option<EmergencyCopy>> = Mutex::new(None);
+
+/// 应急副本写入函数，返回写入的文件路径
+pub type EmergencyCopy = Box<dyn FnOnce() -> io::Result<PathBuf> + Send>;
+
+/// 安装 panic hook 与 SIGTERM、SIGHUP、SIGTSTP、SIGCONT 信号处理。
+///
+/// 主线程 panic 时先恢复终端再输出 panic 信息；收到信号时记录信号，由编辑器主循环负责退出、挂起或重新进入编辑状态。
+/// 终端关闭后主线程可能阻塞在终端读写中，超时仍未退出时写入 [`set_emergency_copy`] 登记的应急副本后直接结束进程
+pub fn install() -> io::Result<()> {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        // 后台线程 panic 时编辑器仍在运行，不能恢复终端
+        if thread::current().name() == Some("main") {
+            restore_terminal();
+        }
+        error!("程序 panic：{}", info);
+        default_hook(info);
+    }));
+
+    let mut signals = Signals::new([SIGTERM, SIGHUP, SIGTSTP, SIGCONT])?;
+    thread::Builder::new()
+        .name("signal".to_string())
+        .spawn(move || {
+            for signal in signals.forever() {
+                match signal {
+                    // 挂起前需要恢复终端，交给主循环按 Ctrl-Z 的方式处理
+                    SIGTSTP => SUSPEND_REQUESTED.store(true, Ordering::Relaxed),
+                    SIGCONT => CONTINUED.store(true, Ordering::Relaxed),
+                    _ => {
+                        SIGNAL.store(signal as usize, Ordering::Relaxed);
+                        thread::sleep(SIGNAL_EXIT_TIMEOUT);
+                        warn!("收到信号 {} 后编辑器未能退出，直接结束进程", signal);
+                        write_emergency_copy();
+                        process::exit(128 + signal);
+                    }
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// 登记应急副本写入函数，存在未保存的修改时由编辑器在内容变化后登记，内容保存后以 None 清除
+pub fn set_emergency_copy(copy: Option<EmergencyCopy>) {
+    *EMERGENCY_COPY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner) = copy;
+}
+
+// 写入登记的应急副本。主线程可能正阻塞在终端输出中，向终端输出提示同样会阻塞，因此只记录日志
+fn write_emergency_copy() {
+    let copy = EMERGENCY_COPY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take();
+    match copy.map(|write| write()) {
+        Some(Ok(path)) => warn!("未保存的修改已写入 {:?}", path),
+        Some(Err(e)) => error!("写入应急副本失败：{}", e),
+        None => {}
+    }
+}
+
+/// 获取收到的终止信号
+pub fn received_signal() -> Option<i32> {
+    let signal = SIGNAL.load(Ordering::Relaxed);
+    (signal != 0).then_some(signal as i32)
+}
+
+/// 是否收到了外部发送的 SIGTSTP，获取后清除
+pub fn take_suspend_request() -> bool {
+    SUSPEND_REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// 是否收到了 SIGCONT，获取后清除
+pub fn take_continued() -> bool {
+    CONTINUED.swap(false, Ordering::Relaxed)
+}
+
+/// 标准输入或标准输出不是终端时（例如通过管道读取内容），以 `/dev/tty` 替换，使按键输入与画面输出均通过终端进行。
+///
+/// 需在读取完标准输入后、进入编辑状态前调用
+pub fn reopen_tty() -> io::Result<()> {
+    let redirected_stdin = !io::stdin().is_terminal();
+    let redirected_stdout = !stdout().is_terminal();
+    if !redirected_stdin && !redirected_stdout {
+        return Ok(());
+    }
+    let tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+    stdout().flush()?;
+    for (fd, redirected) in [
+        (libc::STDIN_FILENO, redirected_stdin),
+        (libc::STDOUT_FILENO, redirected_stdout),
+    ] {
+        if redirected && unsafe { libc::dup2(tty.as_raw_fd(), fd) } < 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    info!(
+        "重新打开终端，标准输入：{}，标准输出：{}",
+        redirected_stdin, redirected_stdout
+    );
+    Ok(())
+}
+
+/// 进入编辑状态：开启 Raw 模式，`alternate_screen` 为 true 时进入备用屏幕，退出后恢复用户原有的终端内容
+pub fn enter_terminal(alternate_screen: bool) -> io::Result<()> {
+    terminal::enable_raw_mode()?;
+    if alternate_screen {
+        execute!(stdout(), terminal::EnterAlternateScreen)?;
+    }
+    ALTERNATE_SCREEN.store(alternate_screen, Ordering::Relaxed);
+    Ok(())
+}
+
+/// 离开编辑状态：离开备用屏幕，未使用备用屏幕时清除屏幕内容，随后显示光标并关闭 Raw 模式
+pub fn leave_terminal() -> io::Result<()> {
+    if ALTERNATE_SCREEN.swap(false, Ordering::Relaxed) {
+        execute!(stdout(), terminal::LeaveAlternateScreen)?;
+    } else {
+        execute!(
+            stdout(),
+            terminal::Clear(ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+    }
+    execute!(stdout(), cursor::Show)?;
+    terminal::disable_raw_mode()
+}
+
+/// 挂起进程：恢复终端后以 SIGTSTP 的默认方式暂停进程，将控制权交还 shell，收到 SIGCONT 继续运行后重新进入编辑状态
+pub fn suspend() -> io::Result<()> {
+    let alternate_screen = ALTERNATE_SCREEN.load(Ordering::Relaxed);
+    leave_terminal()?;
+    info!("挂起编辑器");
+    // SIGTSTP 已由信号线程处理，再次发送只会被记录为挂起请求
+    signal_hook::low_level::emulate_default_handler(SIGTSTP)?;
+    info!("恢复编辑器");
+    enter_terminal(alternate_screen)
+}
+
+/// 重新进入编辑状态：进程被外部暂停（例如 SIGSTOP）后继续运行时，shell 可能已恢复终端模式并覆盖屏幕内容
+pub fn resume() -> io::Result<()> {
+    let alternate_screen = ALTERNATE_SCREEN.load(Ordering::Relaxed);
+    // crossterm 认为已处于 Raw 模式时不会重新设置终端属性，需先关闭再开启
+    terminal::disable_raw_mode()?;
+    info!("继续运行，重新进入编辑状态");
+    enter_terminal(alternate_screen)
+}
+
+/// 查询终端是否支持同步更新（DEC 模式 2026），需在 Raw 模式下调用，查询失败时视为不支持
+pub fn supports_synchronized_output() -> bool {
+    match query_synchronized_output() {
+        Ok(supported) => supported,
+        Err(e) => {
+            warn!("查询终端同步更新支持失败：{}", e);
+            false
+        }
+    }
+}
+
+// 依次发送 DECRQM 模式查询与主设备属性查询。所有终端都会响应主设备属性查询，
+// 且响应按查询顺序返回，收到该响应时即可确定终端是否响应了模式查询，无需等待超时
+fn query_synchronized_output() -> io::Result<bool> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+    tty.write_all(b"\x1b[?2026$p\x1b[c")?;
+    tty.flush()?;
+
+    let deadline = Instant::now() + QUERY_TIMEOUT;
+    let mut response = Vec::new();
+    while !has_device_attributes(&response) {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        let mut poll_fd = libc::pollfd {
+            fd: tty.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        let ready = unsafe { libc::poll(&mut poll_fd, 1, timeout.as_millis() as libc::c_int) };
+        if ready < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        if ready == 0 {
+            warn!(
+                "等待终端响应查询超时：{:?}",
+                String::from_utf8_lossy(&response)
+            );
+            break;
+        }
+        let mut buf = [0; 64];
+        match tty.read(&mut buf)? {
+            0 => break,
+            len => response.extend_from_slice(&buf[..len]),
+        }
+    }
+    // 模式状态响应为 ESC [ ? 2026 ; Ps $ y，Ps 为 1 或 2 时表示支持，3 表示始终开启
+    const MODE_REPORT: &[u8] = b"\x1b[?2026;";
+    let status = find_subslice(&response, MODE_REPORT)
+        .and_then(|start| response.get(start + MODE_REPORT.len()..start + MODE_REPORT.len() + 3));
+    Ok(matches!(status, Some(b"1$y" | b"2$y" | b"3$y")))
+}
+
+// 是否收到了主设备属性响应 ESC [ ? Ps ; ... c
+fn has_device_attributes(response: &[u8]) -> bool {
+    let mut rest = response;
+    while let Some(start) = find_subslice(rest, b"\x1b[?") {
+        rest = &rest[start + 3..];
+        let params = rest
+            .iter()
+            .take_while(|b| b.is_ascii_digit() || **b == b';')
+            .count();
+        if rest.get(params) == Some(&b'c') {
+            return true;
+        }
+    }
+    false
+}
+
+// 查找子串的起始位置
+fn find_subslice(bytes: &[u8], pattern: &[u8]) -> Option<usize> {
+    bytes
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+}
+
+/// 恢复终端：关闭 Raw 模式、离开备用屏幕并显示光标，忽略所有错误。未使用备用屏幕时保留屏幕内容
+pub fn restore_terminal() {
+    let _ = terminal::disable_raw_mode();
+    if ALTERNATE_SCREEN.swap(false, Ordering::Relaxed) {
+        let _ = execute!(stdout(), terminal::LeaveAlternateScreen);
+    }
+    let _ = execute!(stdout(), cursor::Show);
+}
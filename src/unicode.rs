@@ -0,0 +1,47 @@
+use std::cmp;
+use std::iter;
+
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// 获取字形簇在终端中的显示宽度，中日韩文字等宽字符占两列，组合字符不占列
+pub fn grapheme_width(grapheme: &str) -> usize {
+    UnicodeWidthStr::width(grapheme)
+}
+
+/// 获取字符串在终端中的显示宽度
+pub fn str_width(string: &str) -> usize {
+    string.graphemes(true).map(grapheme_width).sum()
+}
+
+/// 截取字符串中位于 [start, end) 列范围内的内容，被范围边界截断的宽字符以空格填充
+pub fn slice_columns(string: &str, start: usize, end: usize) -> String {
+    let mut result = String::new();
+    let mut column = 0;
+    for grapheme in string.graphemes(true) {
+        if column >= end {
+            break;
+        }
+        let next = column + grapheme_width(grapheme);
+        if column >= start && next <= end {
+            result.push_str(grapheme);
+        } else if next > start {
+            let visible = cmp::min(next, end) - cmp::max(column, start);
+            result.extend(iter::repeat_n(' ', visible));
+        }
+        column = next;
+    }
+    result
+}
+
+/// 截断字符串，使其显示宽度不超过指定列数
+pub fn truncate_to_width(string: &str, width: usize) -> &str {
+    let mut column = 0;
+    for (i, grapheme) in string.grapheme_indices(true) {
+        column += grapheme_width(grapheme);
+        if column > width {
+            return &string[..i];
+        }
+    }
+    string
+}
@@ -1,148 +1,281 @@
-use std::cmp;
-use std::io::{stdout, Write};
+use unicode_segmentation::UnicodeSegmentation;
 
-use crossterm::terminal::ClearType;
-use crossterm::{cursor, execute, queue, style, terminal};
+use crate::backend::Backend;
+use crate::buffer::TextBuffer;
+use crate::error::Result;
+use crate::gutter::{Gutter, LineNumberMode};
+use crate::hex_view::HexView;
+use crate::screen::Frame;
+use crate::status::{Status, StatusInfo};
+use crate::unicode::{grapheme_width, slice_columns, str_width, truncate_to_width};
+use crate::{CursorController, VERSION};
 
-use crate::status::StatusInfo;
-use crate::{CursorController, EditorOutput, TAB_SIZE, VERSION};
-
-/// 编辑器内容显示器
+/// 编辑器内容显示器。
+///
+/// 仅负责将 [`TextBuffer`] 渲染为画面并交给后端输出，不持有文本内容
 pub struct EditorView {
     // 终端窗口大小
     win_size: (usize, usize),
     // 终端窗口最大文本行数
     win_max_rows: usize,
-    // 编辑器输出
-    editor_output: EditorOutput,
-    // 文本行
-    edit_rows: Vec<EditRow>,
+    // 终端后端
+    backend: Box<dyn Backend>,
+    // 行号栏
+    gutter: Gutter,
+    // 是否开启软换行
+    soft_wrap: bool,
 }
 
 impl EditorView {
-    // 重置并且隐藏光标
-    fn reset_and_hide_cursor(&mut self) {
-        queue!(self.editor_output, cursor::Hide, cursor::MoveTo(0, 0)).unwrap();
-    }
-
-    // 移动光标到指定位置
-    fn move_cursor(&mut self, cc: &mut CursorController) {
-        let cursor_x = cc.get_cursor().get_x() - cc.get_columns_offset();
-        let cursor_y = cc.get_cursor().get_y() - cc.get_rows_offset();
-        queue!(
-            self.editor_output,
-            cursor::MoveTo(cursor_x as u16, cursor_y as u16),
-            cursor::Show
-        )
-        .unwrap();
-    }
-
     // 绘制 banner
-    fn draw_banner(&mut self) {
+    fn draw_banner(&self, frame: &mut Frame, y: usize) {
         let screen_columns = self.win_size.0;
         let mut welcome_text = format!("z-editor --- version: {}", VERSION);
         if welcome_text.len() > screen_columns {
             welcome_text.truncate(screen_columns);
         }
-        let mut padding = (screen_columns - welcome_text.len()) / 2;
+        let padding = (screen_columns - welcome_text.len()) / 2;
         if padding != 0 {
-            self.editor_output.push('~');
-            padding -= 1;
+            frame.put_str(0, y, "~", false);
         }
-        (0..padding).for_each(|_| self.editor_output.push(' '));
-        self.editor_output.push_str(&welcome_text);
+        frame.put_str(padding, y, &welcome_text, false);
+    }
+
+    // 绘制行号栏，返回文本起始列
+    fn draw_gutter(
+        &self,
+        frame: &mut Frame,
+        buffer: &TextBuffer,
+        y: usize,
+        cursor_row: usize,
+        view_rows: usize,
+    ) -> usize {
+        let gutter = self
+            .gutter
+            .render(view_rows, cursor_row, buffer.number_of_rows());
+        frame.put_str(0, y, &gutter, false)
+    }
+
+    // 绘制软换行模式下某一行的指定分段
+    fn draw_segment(
+        &self,
+        frame: &mut Frame,
+        buffer: &TextBuffer,
+        y: usize,
+        view_rows: usize,
+        (start, end): (usize, usize),
+    ) {
+        let segment = slice_columns(&Self::rendered_row(buffer, view_rows), start, end);
+        frame.put_str(self.gutter_width(buffer), y, &segment, false);
     }
 
     // 绘制文本
-    fn draw_text(&mut self, cc: &mut CursorController, view_rows: usize) {
-        let screen_columns = self.win_size.0;
-        let text = &self.edit_rows[view_rows].rendered_content;
-        let column_offset = cc.get_columns_offset();
-        let mut len = cmp::min(text.len().saturating_sub(column_offset), screen_columns);
-        // Note: 修复中文字符串截取问题
-        while !text.is_char_boundary(len) {
-            len -= 1;
-        }
-        let start = if len == 0 { 0 } else { column_offset };
-        self.editor_output
-            .push_str(&self.edit_rows[view_rows].rendered_content[start..start + len]);
+    fn draw_text(
+        &self,
+        frame: &mut Frame,
+        buffer: &TextBuffer,
+        (x, y): (usize, usize),
+        columns_offset: usize,
+        view_rows: usize,
+    ) {
+        let screen_columns = self.get_text_columns(buffer);
+        // 按显示列截取，宽字符被屏幕边界截断时以空格填充
+        let text = slice_columns(
+            &Self::rendered_row(buffer, view_rows),
+            columns_offset,
+            columns_offset + screen_columns,
+        );
+        frame.put_str(x, y, &text, false);
     }
 
     // 绘制屏幕所有行
-    fn draw_text_rows(&mut self, cc: &mut CursorController) {
-        // 窗口留下两行用于打印其他信息
-        let max_text_rows = self.win_size.1.saturating_sub(2);
+    fn draw_text_rows(&self, frame: &mut Frame, buffer: &TextBuffer, cc: &mut CursorController) {
+        if self.soft_wrap {
+            return self.draw_wrapped_text_rows(frame, buffer, cc);
+        }
+        let max_text_rows = self.win_max_rows;
+        let cursor_row = cc.get_cursor().get_y();
         for i in 0..max_text_rows {
             let view_rows = i + cc.get_rows_offset();
-            if view_rows >= self.edit_rows.len() {
-                // 超出实际文本内容外的行
-                self.editor_output.push('~');
+            if view_rows >= buffer.number_of_rows() {
+                if buffer.number_of_rows() == 0 && i == max_text_rows / 3 {
+                    // 空文件时显示欢迎信息
+                    self.draw_banner(frame, i);
+                } else {
+                    // 超出实际文本内容外的行
+                    frame.put_str(0, i, "~", false);
+                }
             } else {
-                self.draw_text(cc, view_rows);
+                let x = self.draw_gutter(frame, buffer, i, cursor_row, view_rows);
+                self.draw_text(frame, buffer, (x, i), cc.get_columns_offset(), view_rows);
             }
-            queue!(self.editor_output, terminal::Clear(ClearType::UntilNewLine)).unwrap();
-            self.editor_output.push_str("\r\n");
         }
     }
 
-    // 绘制状态栏
-    fn draw_status_bar(&mut self, cc: &mut CursorController, file_name: &str) {
-        self.editor_output
-            .push_str(&style::Attribute::Reverse.to_string());
-        let info = format!("{} -- {} lines", file_name, self.number_of_rows());
-        let info_len = cmp::min(info.len(), self.win_size.0);
-        let line_info = format!("{}/{}", cc.get_cursor().get_y() + 1, self.number_of_rows());
-        self.editor_output.push_str(&info[..info_len]);
-        for i in info_len..self.win_size.0 {
-            if self.win_size.0 - i == line_info.len() {
-                self.editor_output.push_str(&line_info);
-                break;
+    // 软换行模式下绘制屏幕所有行，一个文本行可能占用多个屏幕行
+    fn draw_wrapped_text_rows(
+        &self,
+        frame: &mut Frame,
+        buffer: &TextBuffer,
+        cc: &mut CursorController,
+    ) {
+        let max_text_rows = self.win_max_rows;
+        let cursor_row = cc.get_cursor().get_y();
+        let mut view_rows = cc.get_rows_offset();
+        let mut segment = cc.get_segment_offset();
+        for i in 0..max_text_rows {
+            if view_rows >= buffer.number_of_rows() {
+                if buffer.number_of_rows() == 0 && i == max_text_rows / 3 {
+                    self.draw_banner(frame, i);
+                } else {
+                    frame.put_str(0, i, "~", false);
+                }
             } else {
-                self.editor_output.push(' ');
+                let segments = self.wrap_segments(buffer, view_rows);
+                // 后续分段的行号栏留空
+                if segment == 0 {
+                    self.draw_gutter(frame, buffer, i, cursor_row, view_rows);
+                }
+                self.draw_segment(frame, buffer, i, view_rows, segments[segment]);
+                segment += 1;
+                if segment >= segments.len() {
+                    view_rows += 1;
+                    segment = 0;
+                }
             }
         }
-        self.editor_output
-            .push_str(&style::Attribute::Reset.to_string());
-        self.editor_output.push_str("\r\n");
+    }
+
+    // 绘制状态栏，右侧信息在宽度不足时不显示
+    fn draw_status_bar(&self, frame: &mut Frame, info: &str, line_info: &str) {
+        let y = self.win_max_rows;
+        frame.fill(0, y, true);
+        let info = truncate_to_width(info, self.win_size.0);
+        let info_len = frame.put_str(0, y, info, true);
+        let line_info_len = str_width(line_info);
+        if info_len + line_info_len <= self.win_size.0 {
+            frame.put_str(self.win_size.0 - line_info_len, y, line_info, true);
+        }
     }
 
     // 绘制消息栏
-    fn draw_message_bar(&mut self, status_info: &StatusInfo) {
-        queue!(self.editor_output, terminal::Clear(ClearType::UntilNewLine)).unwrap();
+    fn draw_message_bar(&self, frame: &mut Frame, status_info: &StatusInfo) {
         let msg = status_info.get_message();
-        self.editor_output
-            .push_str(&msg[..cmp::min(self.win_size.0, msg.len())]);
+        frame.put_str(0, self.win_max_rows + 1, msg, false);
+    }
+
+    // 通过后端输出新的一帧
+    fn draw_frame(&mut self, frame: Frame) -> Result<()> {
+        self.backend.draw(frame)?;
+        Ok(())
     }
 
-    /// 创建编辑器内容显示器
-    pub fn new(content: Vec<String>) -> Self {
-        let win_size = terminal::size()
-            .map(|(x, y)| (x as usize, y as usize))
-            .unwrap();
+    /// 创建编辑器内容显示器，窗口大小由后端提供
+    pub fn new(backend: Box<dyn Backend>) -> Result<Self> {
+        let win_size = backend.size()?;
         let win_max_rows = win_size.1.saturating_sub(2);
         info!("创建编辑视图，窗口大小为：{:?}", win_size);
-        Self {
+        Ok(Self {
             win_size,
             win_max_rows,
-            editor_output: EditorOutput::new(),
-            edit_rows: content.into_iter().map(|it| EditRow::new(it)).collect(),
-        }
+            backend,
+            gutter: Gutter::new(LineNumberMode::Absolute),
+            soft_wrap: false,
+        })
     }
 
-    /// 清除当前终端所有内容
-    pub fn clear_screen() -> crossterm::Result<()> {
-        execute!(stdout(), terminal::Clear(ClearType::All))?;
-        execute!(stdout(), cursor::MoveTo(0, 0))
+    /// 刷新屏幕，内容没有变化时不输出任何内容
+    pub fn refresh_screen(
+        &mut self,
+        buffer: &TextBuffer,
+        cc: &mut CursorController,
+        status_info: &StatusInfo,
+    ) -> Result<()> {
+        let mut frame = Frame::new(self.win_size.0, self.win_size.1);
+        self.draw_text_rows(&mut frame, buffer, cc);
+        // 行索引建立完成前，总行数仅为已扫描的行数
+        let indexing = if buffer.is_indexing() { "+" } else { "" };
+        // 映射的文件被截断后不再显示内容
+        let truncated = if buffer.is_truncated() {
+            " [truncated]"
+        } else {
+            ""
+        };
+        let info = format!(
+            "{} -- {}{} lines{}{}",
+            status_info.file_name_or_default(),
+            buffer.number_of_rows(),
+            indexing,
+            Self::status_text(status_info),
+            truncated
+        );
+        // 文件末尾缺少换行符时以 [noeol] 标记
+        let noeol = if buffer.has_final_newline() {
+            ""
+        } else {
+            " [noeol]"
+        };
+        let line_info = format!(
+            "{} | {}{} | {}/{}",
+            status_info.get_encoding().name(),
+            buffer.line_ending().name(),
+            noeol,
+            cc.get_cursor().get_y() + 1,
+            buffer.number_of_rows()
+        );
+        self.draw_status_bar(&mut frame, &info, &line_info);
+        self.draw_message_bar(&mut frame, status_info);
+        let (screen_x, cursor_y) = cc.screen_position(buffer, self);
+        frame.set_cursor(screen_x + self.gutter_width(buffer), cursor_y);
+        self.draw_frame(frame)
     }
 
-    /// 刷新屏幕
-    pub fn refresh_screen(&mut self, cc: &mut CursorController, status_info: &StatusInfo) {
-        self.reset_and_hide_cursor();
-        self.draw_text_rows(cc);
-        self.draw_status_bar(cc, status_info.file_name_or_default());
-        self.draw_message_bar(status_info);
-        self.move_cursor(cc);
-        self.editor_output.flush().unwrap();
+    /// 以十六进制视图刷新屏幕
+    pub fn refresh_hex_screen(
+        &mut self,
+        hex_view: &HexView,
+        status_info: &StatusInfo,
+    ) -> Result<()> {
+        let mut frame = Frame::new(self.win_size.0, self.win_size.1);
+        for i in 0..self.win_max_rows {
+            let row = i + hex_view.get_rows_offset();
+            if row < hex_view.number_of_rows() {
+                frame.put_str(0, i, &hex_view.render_row(row), false);
+            } else {
+                frame.put_str(0, i, "~", false);
+            }
+        }
+        let mode = if hex_view.is_editing() {
+            "hex edit"
+        } else {
+            "hex"
+        };
+        let info = format!(
+            "{} -- {} bytes [{}]{}",
+            status_info.file_name_or_default(),
+            hex_view.number_of_bytes(),
+            mode,
+            Self::status_text(status_info)
+        );
+        let line_info = format!(
+            "{}/{}",
+            hex_view.cursor_row() + 1,
+            hex_view.number_of_rows()
+        );
+        self.draw_status_bar(&mut frame, &info, &line_info);
+        self.draw_message_bar(&mut frame, status_info);
+        let (x, y) = hex_view.screen_position();
+        frame.set_cursor(x, y);
+        self.draw_frame(frame)
+    }
+
+    // 状态栏中显示的文件状态
+    fn status_text(status_info: &StatusInfo) -> &'static str {
+        match status_info.get_status() {
+            Status::Modified => " (modified)",
+            Status::ReadOnly => " (read-only)",
+            Status::Saved => "",
+        }
     }
 
     /// 获取窗口大小
@@ -150,78 +283,101 @@ impl EditorView {
         self.win_size
     }
 
+    /// 重新获取终端窗口大小，窗口大小可能在编辑器挂起期间发生变化
+    pub fn update_win_size(&mut self) -> Result<()> {
+        self.win_size = self.backend.size()?;
+        self.win_max_rows = self.win_size.1.saturating_sub(2);
+        // 窗口大小未变时终端内容也可能已被清除，需要完整重绘
+        self.backend.invalidate();
+        info!("窗口大小变更为：{:?}", self.win_size);
+        Ok(())
+    }
+
+    /// 进入编辑状态
+    pub fn enter_terminal(&mut self) -> Result<()> {
+        self.backend.enter()?;
+        Ok(())
+    }
+
+    /// 挂起编辑器，恢复运行后重新获取窗口大小
+    pub fn suspend(&mut self) -> Result<()> {
+        self.backend.suspend()?;
+        self.update_win_size()
+    }
+
+    /// 被外部暂停后继续运行时重新进入编辑状态，并重新获取窗口大小
+    pub fn resume(&mut self) -> Result<()> {
+        self.backend.resume()?;
+        self.update_win_size()
+    }
+
+    /// 获取终端窗口最大文本行数，即窗口行数减去状态栏与消息栏
     pub fn get_win_max_rows(&self) -> usize {
         self.win_max_rows
     }
 
-    /// 获取文本内容总行数
-    pub fn number_of_rows(&self) -> usize {
-        self.edit_rows.len()
+    /// 获取行号栏宽度，无文本内容时不显示行号栏
+    pub fn gutter_width(&self, buffer: &TextBuffer) -> usize {
+        if buffer.number_of_rows() == 0 {
+            0
+        } else {
+            self.gutter.width(buffer.number_of_rows())
+        }
     }
 
-    /// 获取指定行的渲染内容
-    pub fn rendered_content_of_row(&self, i: usize) -> &str {
-        self.edit_rows[i].get_rendered_content()
+    /// 获取可用于显示文本的列数，即窗口宽度减去行号栏宽度
+    pub fn get_text_columns(&self, buffer: &TextBuffer) -> usize {
+        self.win_size.0.saturating_sub(self.gutter_width(buffer))
     }
 
-    /// 获取指定行的原始内容
-    pub fn raw_content_of_row(&self, i: usize) -> &str {
-        self.edit_rows[i].get_raw_content()
+    /// 切换绝对行号与相对行号
+    pub fn toggle_line_number_mode(&mut self) {
+        self.gutter.toggle_mode();
     }
 
-    /// 获取指定行
-    pub fn get_edit_row(&self, i: usize) -> &EditRow {
-        &self.edit_rows[i]
+    /// 是否开启软换行
+    pub fn is_soft_wrap(&self) -> bool {
+        self.soft_wrap
     }
-}
 
-impl Drop for EditorView {
-    fn drop(&mut self) {
-        EditorView::clear_screen().expect("Error");
+    /// 切换软换行模式
+    pub fn toggle_soft_wrap(&mut self) {
+        self.soft_wrap = !self.soft_wrap;
     }
-}
 
-/// 编辑行
-pub struct EditRow {
-    // 原内容
-    raw_content: String,
-    // 渲染内容
-    rendered_content: String,
-}
-
-impl EditRow {
-    /// 创建编辑行
-    pub fn new(raw_content: String) -> Self {
-        let mut index = 0;
-        let capacity = raw_content
-            .chars()
-            .fold(0, |acc, next| acc + if next == '\t' { TAB_SIZE } else { 1 });
-        let mut rendered_content = String::with_capacity(capacity);
-        raw_content.chars().for_each(|c| {
-            index += 1;
-            if c == '\t' {
-                rendered_content.push(' ');
-                while index % TAB_SIZE != 0 {
-                    rendered_content.push(' ');
-                    index += 1;
-                }
-            } else {
-                rendered_content.push(c);
+    /// 获取指定行在软换行模式下的分段，每段为渲染内容中的 [起始列, 结束列)。
+    ///
+    /// 未开启软换行、空行以及文本末尾之后的行均只有一个分段。宽字符不会被拆分到两个分段中。
+    /// 软换行模式下最后一个分段恰好占满文本区域时，末尾追加一个空分段，使行末的光标显示在下一个屏幕行开头
+    pub fn wrap_segments(&self, buffer: &TextBuffer, i: usize) -> Vec<(usize, usize)> {
+        if i >= buffer.number_of_rows() {
+            return vec![(0, 0)];
+        }
+        let rendered_content = Self::rendered_row(buffer, i);
+        let width = str_width(&rendered_content);
+        let columns = self.get_text_columns(buffer).max(1);
+        if !self.soft_wrap || width < columns {
+            return vec![(0, width)];
+        }
+        let mut segments = Vec::new();
+        let (mut start, mut column) = (0, 0);
+        for grapheme in rendered_content.graphemes(true) {
+            let next = column + grapheme_width(grapheme);
+            if next - start > columns && column > start {
+                segments.push((start, column));
+                start = column;
             }
-        });
-        Self {
-            raw_content,
-            rendered_content,
+            column = next;
         }
+        segments.push((start, column));
+        if column - start == columns {
+            segments.push((column, column));
+        }
+        segments
     }
 
-    /// 获取原内容
-    pub fn get_raw_content(&self) -> &str {
-        self.raw_content.as_str()
-    }
-
-    /// 获取渲染内容
-    pub fn get_rendered_content(&self) -> &str {
-        self.rendered_content.as_str()
+    // 获取指定行的渲染内容，渲染内容仅在绘制时生成
+    fn rendered_row(buffer: &TextBuffer, i: usize) -> String {
+        buffer.layout().render(&buffer.row(i))
     }
 }
@@ -0,0 +1,138 @@
+//! 命令行参数解析与按命令行选项打开文件的测试
+
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use log::LevelFilter;
+
+use z_editor::cli::{self, FileArg, Invocation, Options};
+use z_editor::{Editor, HeadlessBackend};
+
+// tests/fixtures 下的文件
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+// 解析命令行参数，期望得到编辑选项
+fn parse(args: &[&str]) -> Options {
+    match cli::parse(args.iter().map(|arg| arg.to_string())) {
+        Ok(Invocation::Edit(options)) => options,
+        other => panic!("期望得到编辑选项：{:?}", other),
+    }
+}
+
+// 解析命令行参数，期望得到错误信息
+fn parse_error(args: &[&str]) -> String {
+    cli::parse(args.iter().map(|arg| arg.to_string())).unwrap_err()
+}
+
+// 在内存后端中打开文件并输入按键，返回后端
+fn open(files: Vec<FileArg>, keys: &[KeyEvent]) -> HeadlessBackend {
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open_files(files, false, Box::new(backend.clone())).unwrap();
+    for key in keys {
+        assert!(!editor.handle_event(Event::Key(*key)).unwrap());
+    }
+    editor.refresh_screen().unwrap();
+    backend
+}
+
+#[test]
+fn help_and_version_stop_parsing() {
+    let parse = |args: &[&str]| cli::parse(args.iter().map(|arg| arg.to_string()));
+    assert_eq!(parse(&["a.txt", "--help"]), Ok(Invocation::Help));
+    assert_eq!(parse(&["-V", "--bogus"]), Ok(Invocation::Version));
+}
+
+#[test]
+fn parses_files_and_flags() {
+    let options = parse(&[
+        "-R",
+        "--config",
+        "z.conf",
+        "--log-file=z.log",
+        "--log-level",
+        "debug",
+        "a.txt",
+        "b.txt",
+    ]);
+    assert!(options.read_only());
+    assert_eq!(options.config(), Some(Path::new("z.conf")));
+    assert_eq!(options.log_file(), Some(Path::new("z.log")));
+    assert_eq!(options.log_level(), Some(LevelFilter::Debug));
+    let paths: Vec<_> = options.files().iter().map(FileArg::path).collect();
+    assert_eq!(paths, [Some(Path::new("a.txt")), Some(Path::new("b.txt"))]);
+}
+
+#[test]
+fn plus_line_applies_to_next_file() {
+    let options = parse(&["a.txt", "+12", "b.txt"]);
+    assert_eq!(options.files()[0].line(), None);
+    assert_eq!(options.files()[1].line(), Some(12));
+    assert!(parse_error(&["a.txt", "+3"]).contains("缺少文件"));
+    assert!(parse_error(&["+0", "a.txt"]).contains("无效的行号"));
+}
+
+#[test]
+fn line_and_column_suffix_is_split_from_missing_files() {
+    let options = parse(&["missing.txt:3:7", "missing.txt:5", "missing.txt:x"]);
+    let files = options.files();
+    assert_eq!(
+        files[0],
+        FileArg::new(Some("missing.txt".into()), Some(3), Some(7))
+    );
+    assert_eq!(
+        files[1],
+        FileArg::new(Some("missing.txt".into()), Some(5), None)
+    );
+    assert_eq!(
+        files[2],
+        FileArg::new(Some("missing.txt:x".into()), None, None)
+    );
+}
+
+#[test]
+fn dash_reads_stdin_once() {
+    let options = parse(&["-", "--", "-R"]);
+    assert_eq!(options.files()[0].path(), None);
+    assert_eq!(options.files()[1].path(), Some(Path::new("-R")));
+    assert!(!options.read_only());
+    assert!(parse_error(&["-", "-"]).contains("标准输入"));
+}
+
+#[test]
+fn unknown_flag_is_an_error() {
+    assert_eq!(parse_error(&["--bogus"]), "无法识别的选项：--bogus");
+    assert_eq!(parse_error(&["--config"]), "选项 --config 缺少参数");
+    assert_eq!(parse_error(&["--log-level=loud"]), "无效的日志级别：loud");
+}
+
+#[test]
+fn opens_file_at_line_and_column() {
+    let file = FileArg::new(Some(fixture("lines.txt")), Some(20), Some(4));
+    let backend = open(vec![file], &[]);
+    // 光标移出首屏后滚动至可见区域
+    assert_eq!(backend.row_text(7), "  20 line 20");
+    assert_eq!(backend.cursor(), Some((8, 7)));
+}
+
+#[test]
+fn switches_between_files() {
+    let files = vec![
+        FileArg::new(Some(fixture("lines.txt")), None, None),
+        FileArg::new(Some(fixture("sample.txt")), None, None),
+    ];
+    let next = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::ALT);
+    let backend = open(files.clone(), &[next]);
+    assert!(backend.status_bar().starts_with("sample.txt"));
+    assert!(backend.message_bar().starts_with("文件 2/2：sample.txt"));
+
+    // 存在未保存的修改时拒绝切换
+    let edit = KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE);
+    let backend = open(files, &[edit, next]);
+    assert!(backend.status_bar().starts_with("lines.txt"));
+    assert_eq!(backend.message_bar(), "存在未保存的修改，请先保存");
+}
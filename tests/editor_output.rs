@@ -0,0 +1,44 @@
+//! 编辑器输出缓冲与同步更新的测试
+
+use std::io::Write;
+
+use z_editor::editor_output::EditorOutput;
+
+// 分两次写入并刷新，返回输出流收到的字节
+fn write_frame(synchronized: bool, parts: &[&[u8]]) -> Vec<u8> {
+    let mut output = EditorOutput::with_writer(Vec::new());
+    output.set_synchronized(synchronized);
+    for part in parts {
+        output.write_all(part).unwrap();
+    }
+    output.flush().unwrap();
+    output.get_ref().clone()
+}
+
+#[test]
+fn frame_is_wrapped_only_when_synchronized() {
+    assert_eq!(write_frame(false, &[b"abc"]), b"abc");
+    assert_eq!(write_frame(true, &[b"abc"]), b"\x1b[?2026habc\x1b[?2026l");
+}
+
+#[test]
+fn split_multibyte_character_arrives_intact() {
+    let bytes = "世".as_bytes();
+    let output = write_frame(false, &[&bytes[..2], &bytes[2..]]);
+    assert_eq!(String::from_utf8(output).unwrap(), "世");
+}
+
+#[test]
+fn buffer_is_empty_after_flush() {
+    let mut output = EditorOutput::with_writer(Vec::new());
+    output.set_synchronized(true);
+    output.write_all(b"abc").unwrap();
+    output.flush().unwrap();
+    let len = output.get_ref().len();
+    // 缓冲区已清空，再次刷新不输出任何内容，也不输出空的同步更新
+    output.flush().unwrap();
+    assert_eq!(output.get_ref().len(), len);
+    output.write_all(b"d").unwrap();
+    output.flush().unwrap();
+    assert!(output.get_ref().ends_with(b"\x1b[?2026hd\x1b[?2026l"));
+}
@@ -0,0 +1,138 @@
+//! 文件编码检测与解码、编码往返的测试
+
+use std::env;
+use std::fs;
+use std::process;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use z_editor::encoding::FileEncoding;
+use z_editor::{Editor, HeadlessBackend};
+
+// 检测编码并解码，确认重新编码后与原内容一致，返回编码名称与文本
+fn round_trip(bytes: &[u8]) -> (String, String) {
+    let encoding = FileEncoding::detect(bytes);
+    let (text, had_errors) = encoding.decode(bytes);
+    assert!(!had_errors, "{} 解码失败", encoding.name());
+    assert_eq!(
+        encoding.encode(&text).unwrap(),
+        bytes,
+        "{}",
+        encoding.name()
+    );
+    (encoding.name(), text)
+}
+
+// 以指定编码编码文本
+fn encode(label: &str, text: &str) -> Vec<u8> {
+    FileEncoding::for_label(label)
+        .unwrap()
+        .encode(text)
+        .unwrap()
+}
+
+#[test]
+fn gbk_round_trip() {
+    let bytes = b"\xD6\xD0\xCE\xC4 abc\n";
+    assert_eq!(
+        round_trip(bytes),
+        ("GBK".to_string(), "中文 abc\n".to_string())
+    );
+}
+
+#[test]
+fn gb18030_four_byte_sequences_are_not_gbk() {
+    let bytes = encode("gb18030", "中文😀\n");
+    assert_eq!(
+        round_trip(&bytes),
+        ("gb18030".to_string(), "中文😀\n".to_string())
+    );
+    let gbk = FileEncoding::for_label("gbk").unwrap();
+    assert!(gbk.encode("😀").is_err());
+}
+
+#[test]
+fn utf16_with_bom_round_trip() {
+    let le = b"\xFF\xFEa\x00-N\n\x00";
+    assert_eq!(
+        round_trip(le),
+        ("UTF-16LE".to_string(), "a中\n".to_string())
+    );
+    let be = b"\xFE\xFF\x00aN-\x00\n";
+    assert_eq!(
+        round_trip(be),
+        ("UTF-16BE".to_string(), "a中\n".to_string())
+    );
+}
+
+#[test]
+fn utf8_with_bom_round_trip() {
+    let bytes = b"\xEF\xBB\xBF\xE4\xB8\xAD\n";
+    assert_eq!(
+        round_trip(bytes),
+        ("UTF-8 BOM".to_string(), "中\n".to_string())
+    );
+    assert_eq!(round_trip(b"plain\n").0, "UTF-8");
+}
+
+#[test]
+fn invalid_bytes_fall_back_to_latin1() {
+    let bytes = b"caf\xE9 cr\xE8me\n";
+    assert_eq!(
+        round_trip(bytes),
+        ("windows-1252".to_string(), "café crème\n".to_string())
+    );
+}
+
+#[test]
+fn reopening_keeps_the_bom_state_of_the_file() {
+    let without_bom = b"a\x00\n\x00";
+    let encoding = FileEncoding::for_label("utf-16le")
+        .unwrap()
+        .with_bom_of(without_bom);
+    let (text, had_errors) = encoding.decode(without_bom);
+    assert!(!had_errors);
+    assert_eq!(encoding.encode(&text).unwrap(), without_bom);
+
+    let with_bom = b"\xEF\xBB\xBFa\n";
+    let encoding = FileEncoding::for_label("utf-8")
+        .unwrap()
+        .with_bom_of(with_bom);
+    assert_eq!(encoding.name(), "UTF-8 BOM");
+    assert_eq!(encoding.encode("a\n").unwrap(), with_bom);
+}
+
+// 输入一个按键
+fn press(editor: &mut Editor, code: KeyCode, modifiers: KeyModifiers) {
+    let event = Event::Key(KeyEvent::new(code, modifiers));
+    assert!(!editor.handle_event(event).unwrap());
+}
+
+// 在消息栏中输入文本并确认
+fn answer(editor: &mut Editor, input: &str) {
+    for c in input.chars() {
+        press(editor, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press(editor, KeyCode::Enter, KeyModifiers::NONE);
+}
+
+#[test]
+fn undecodable_bytes_require_conversion_before_saving() {
+    let path = env::temp_dir().join(format!("z-editor-undecodable-{}.txt", process::id()));
+    fs::write(&path, b"caf\xE9\n").unwrap();
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open(Some(&path), Box::new(backend)).unwrap();
+    press(&mut editor, KeyCode::Char('r'), KeyModifiers::ALT);
+    answer(&mut editor, "utf-8");
+
+    // 直接保存会以替换字符覆盖无法解码的字节
+    press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(fs::read(&path).unwrap(), b"caf\xE9\n");
+
+    // 显式转换编码后才能保存
+    press(&mut editor, KeyCode::Char('e'), KeyModifiers::ALT);
+    answer(&mut editor, "utf-8");
+    press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "caf\u{FFFD}\n");
+    fs::remove_file(&path).unwrap();
+}
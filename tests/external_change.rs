@@ -0,0 +1,60 @@
+//! 文件在外部被修改时的测试
+
+use std::env;
+use std::fs;
+use std::process;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use z_editor::{Editor, HeadlessBackend};
+
+// 输入一个按键
+fn press(editor: &mut Editor, code: KeyCode, modifiers: KeyModifiers) {
+    let event = Event::Key(KeyEvent::new(code, modifiers));
+    assert!(!editor.handle_event(event).unwrap());
+}
+
+#[test]
+fn cancelled_prompt_does_not_allow_overwrite() {
+    let path = env::temp_dir().join(format!("z-editor-external-{}.txt", process::id()));
+    fs::write(&path, "abc\n").unwrap();
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open(Some(&path), Box::new(backend)).unwrap();
+    press(&mut editor, KeyCode::Char('x'), KeyModifiers::NONE);
+    fs::write(&path, "external\n").unwrap();
+
+    // 保存前提示外部修改，按 Esc 取消后再次保存仍应提示，而不是覆盖外部修改
+    for _ in 0..2 {
+        press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+        press(&mut editor, KeyCode::Esc, KeyModifiers::NONE);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "external\n");
+    }
+
+    // 选择保留当前内容后才覆盖
+    press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    press(&mut editor, KeyCode::Char('k'), KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Enter, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "xabc\n");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn reopening_with_encoding_takes_a_new_snapshot() {
+    let path = env::temp_dir().join(format!("z-editor-reopen-{}.txt", process::id()));
+    fs::write(&path, "abc\n").unwrap();
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open(Some(&path), Box::new(backend)).unwrap();
+    fs::write(&path, "external\n").unwrap();
+
+    // 重新打开后读取的是外部修改后的内容，保存时不应再提示外部修改
+    press(&mut editor, KeyCode::Char('r'), KeyModifiers::ALT);
+    for c in "utf-8".chars() {
+        press(&mut editor, KeyCode::Char(c), KeyModifiers::NONE);
+    }
+    press(&mut editor, KeyCode::Enter, KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Char('x'), KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "xexternal\n");
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,39 @@
+//! 日志文件轮转与内存中最近日志的测试。日志记录器全局只能初始化一次，因此单独作为一个测试程序
+
+use std::env;
+use std::fs;
+
+use log::{debug, info, LevelFilter};
+use z_editor::logger;
+
+#[test]
+fn rotates_log_file_and_keeps_recent_lines() {
+    let dir = env::temp_dir().join(format!("z-editor-logger-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let path = dir.join("z-editor.log");
+    logger::init(Some(&path), Some(LevelFilter::Info)).unwrap();
+    assert_eq!(logger::log_path(), Some(path.as_path()));
+
+    debug!("低于日志级别的日志");
+    let line = "x".repeat(1000);
+    let count = logger::MAX_LOG_SIZE as usize / line.len() + 10;
+    for i in 0..count {
+        info!("{} {}", i, line);
+    }
+    log::logger().flush();
+
+    let rotated = dir.join("z-editor.log.1");
+    assert!(rotated.exists());
+    let size = fs::metadata(&rotated).unwrap().len();
+    assert!(size < logger::MAX_LOG_SIZE);
+    let current = fs::read_to_string(&path).unwrap();
+    assert!(current.contains(&format!("{} {}", count - 1, line)));
+
+    let recent = logger::recent_lines();
+    assert!(recent.iter().all(|line| !line.contains("低于日志级别")));
+    assert!(recent
+        .last()
+        .unwrap()
+        .ends_with(&format!("{} {}", count - 1, line)));
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,113 @@
+//! 以内存映射方式只读打开的超大文件的测试
+
+use std::env;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::thread;
+use std::time::Duration;
+
+use z_editor::buffer::ReadOnlyError;
+use z_editor::mapped_file::MappedFile;
+use z_editor::{CursorController, EditorView, HeadlessBackend, Position, TextBuffer};
+
+// 写入临时文件并以内存映射方式打开，等待行索引建立完成
+fn open_mapped(name: &str, content: &str) -> (PathBuf, TextBuffer) {
+    let path = env::temp_dir().join(format!("z-editor-{}-{}.txt", name, std::process::id()));
+    fs::write(&path, content).unwrap();
+    let buffer = TextBuffer::from_mapped_file(MappedFile::open(&path).unwrap());
+    while buffer.is_indexing() {
+        thread::sleep(Duration::from_millis(1));
+    }
+    (path, buffer)
+}
+
+#[test]
+fn mapped_buffer_rejects_edits() {
+    let (path, mut buffer) = open_mapped("mapped-edit", "first\nsecond\n");
+    assert!(buffer.is_read_only());
+    assert_eq!(buffer.number_of_rows(), 2);
+    assert_eq!(buffer.row(1), "second");
+    assert_eq!(buffer.text(), None);
+    assert_eq!(buffer.insert(0, 0, "x"), Err(ReadOnlyError));
+    assert_eq!(buffer.remove(0, 0..1), Err(ReadOnlyError));
+    assert_eq!(buffer.join_with_next_row(0), Err(ReadOnlyError));
+    assert!(buffer.write_to(Vec::new()).is_err());
+    assert_eq!(buffer.row(0), "first");
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn truncated_file_is_not_read() {
+    let (path, buffer) = open_mapped("mapped-truncate", "first\nsecond\n");
+    assert!(!buffer.is_truncated());
+    // 日志轮转等场景下文件被截断，继续读取映射会触发 SIGBUS
+    OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(0)
+        .unwrap();
+    // 截断只报告一次
+    assert!(buffer.check_truncation());
+    assert!(!buffer.check_truncation());
+    assert_eq!(buffer.row(1), "");
+    assert!(buffer.is_truncated());
+    fs::remove_file(&path).unwrap();
+}
+
+// 光标位于已失效的位置时，渲染与移动不应越界或切分字符
+fn assert_cursor_survives(buffer: &TextBuffer, position: Position) {
+    let view = EditorView::new(Box::new(HeadlessBackend::new(40, 10))).unwrap();
+    let mut cursor = CursorController::new();
+    cursor.set_position(buffer, position);
+    cursor.scroll(buffer, &view);
+    buffer.next_grapheme(position);
+    buffer.prev_grapheme(position);
+    buffer.word_forward(position);
+    buffer.word_backward(position);
+    buffer.matching_bracket(position);
+    cursor.move_right(buffer);
+    cursor.move_left(buffer);
+    cursor.scroll(buffer, &view);
+}
+
+#[test]
+fn cursor_survives_truncation() {
+    let (path, buffer) = open_mapped("mapped-cursor-truncate", "first\nsecond\n");
+    OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .set_len(0)
+        .unwrap();
+    assert!(buffer.check_truncation());
+    assert_cursor_survives(&buffer, Position::new(1, 3));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn cursor_survives_rewrite_in_place() {
+    let (path, buffer) = open_mapped("mapped-rewrite", "ab(é)\n");
+    // 原地改写后多字节字符移动到光标所在位置
+    OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .unwrap()
+        .write_all("é(ab)\n".as_bytes())
+        .unwrap();
+    assert!(!buffer.check_truncation());
+    assert_eq!(buffer.row(0), "é(ab)");
+    assert_eq!(buffer.render_x(Position::new(0, 1)), 0);
+    assert_eq!(
+        buffer.next_grapheme(Position::new(0, 1)),
+        Position::new(0, 2)
+    );
+    assert_eq!(
+        buffer.matching_bracket(Position::new(0, 2)),
+        Some(Position::new(0, 5))
+    );
+    assert_cursor_survives(&buffer, Position::new(0, 1));
+    assert_cursor_survives(&buffer, Position::new(0, 9));
+    fs::remove_file(&path).unwrap();
+}
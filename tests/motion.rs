@@ -0,0 +1,71 @@
+//! 不依赖渲染后端的光标移动测试
+
+use z_editor::{CursorController, Layout, Position, TextBuffer};
+
+// 创建位置
+fn at(row: usize, x: usize) -> Position {
+    Position::new(row, x)
+}
+
+#[test]
+fn grapheme_motions_skip_whole_clusters() {
+    let buffer = TextBuffer::from_text("e\u{301}世👍🏽\nb");
+    let mut position = at(0, 0);
+    let mut stops = Vec::new();
+    while position != at(1, 0) {
+        position = buffer.next_grapheme(position);
+        stops.push(position);
+    }
+    assert_eq!(stops, [at(0, 3), at(0, 6), at(0, 14), at(1, 0)]);
+    assert_eq!(buffer.prev_grapheme(at(1, 0)), at(0, 14));
+    assert_eq!(buffer.prev_grapheme(at(0, 14)), at(0, 6));
+    assert_eq!(buffer.prev_grapheme(at(0, 0)), at(0, 0));
+}
+
+#[test]
+fn line_and_buffer_motions_stay_in_range() {
+    let buffer = TextBuffer::from_text("first\n\nsecond paragraph\nabc");
+    assert_eq!(buffer.line_end(at(2, 3)), at(2, 16));
+    // 没有末尾换行符时，最后一行之后的位置不动
+    assert_eq!(buffer.line_end(at(4, 0)), at(4, 0));
+    assert_eq!(buffer.next_grapheme(at(4, 0)), at(4, 0));
+    assert_eq!(buffer.buffer_end(), at(3, 3));
+    assert_eq!(buffer.paragraph_forward(at(0, 2)), at(1, 0));
+    assert_eq!(buffer.paragraph_backward(at(3, 1)), at(1, 0));
+    assert_eq!(buffer.word_forward(at(2, 0)), at(2, 7));
+    assert_eq!(buffer.word_backward(at(2, 0)), at(1, 0));
+}
+
+#[test]
+fn layout_controls_render_columns() {
+    let mut buffer = TextBuffer::from_text("\tx\n宽字\n");
+    assert_eq!(buffer.render_x(at(0, 1)), 8);
+    buffer.set_layout(Layout::new(4));
+    assert_eq!(buffer.render_x(at(0, 1)), 4);
+    assert_eq!(buffer.row_width(0), 5);
+    assert_eq!(buffer.layout().render("\tx"), "    x");
+    // 渲染列位于制表符或宽字符中间时取其起始位置
+    assert_eq!(buffer.position_at_render_x(0, 2), at(0, 0));
+    assert_eq!(buffer.position_at_render_x(1, 3), at(1, 3));
+    assert_eq!(buffer.position_at_render_x(9, 3), at(2, 0));
+}
+
+#[test]
+fn cursor_moves_and_edits_without_backend() {
+    let mut buffer = TextBuffer::from_text("abc\n世界\n");
+    let mut cursor = CursorController::new();
+    cursor.move_end(&buffer);
+    assert_eq!(cursor.position(), at(0, 3));
+    // 下移时保持渲染列，位于宽字符中间时停在其起始位置
+    cursor.move_down(&buffer);
+    assert_eq!(cursor.position(), at(1, 3));
+    cursor.move_right(&buffer);
+    assert_eq!(cursor.position(), at(1, 6));
+    assert!(cursor.delete_backward(&mut buffer).unwrap());
+    assert_eq!(buffer.row(1), "世");
+    cursor.move_down(&buffer);
+    cursor.move_end(&buffer);
+    assert_eq!(cursor.position(), at(2, 0));
+    cursor.insert_char(&mut buffer, '!').unwrap();
+    assert_eq!(buffer.text().as_deref(), Some("abc\n世\n!"));
+}
@@ -0,0 +1,78 @@
+//! 影子屏幕差异输出的测试
+
+use crossterm::{cursor, queue, terminal};
+
+use z_editor::screen::{Frame, Screen};
+
+// 创建包含指定文本的帧，光标位于左上角
+fn frame(width: usize, height: usize, rows: &[&str]) -> Frame {
+    let mut frame = Frame::new(width, height);
+    for (y, row) in rows.iter().enumerate() {
+        frame.put_str(0, y, row, false);
+    }
+    frame.set_cursor(0, 0);
+    frame
+}
+
+// 输出一帧，返回输出的字节
+fn draw(screen: &mut Screen, frame: Frame) -> Vec<u8> {
+    let mut output = Vec::new();
+    screen.draw(frame, &mut output).unwrap();
+    output
+}
+
+// 只修改了部分单元格时的预期输出：隐藏光标、变化的内容、移动并显示光标
+fn expected(changes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    queue!(output, cursor::Hide).unwrap();
+    output.extend_from_slice(changes);
+    queue!(output, cursor::MoveTo(0, 0), cursor::Show).unwrap();
+    output
+}
+
+#[test]
+fn unchanged_frame_emits_nothing() {
+    let mut screen = Screen::new();
+    draw(&mut screen, frame(10, 2, &["abc", "def"]));
+    let mut output = Vec::new();
+    let drawn = screen
+        .draw(frame(10, 2, &["abc", "def"]), &mut output)
+        .unwrap();
+    assert!(!drawn);
+    assert!(output.is_empty());
+}
+
+#[test]
+fn changed_cell_emits_one_move_and_the_cell() {
+    let mut screen = Screen::new();
+    draw(&mut screen, frame(10, 2, &["abc", "def"]));
+    let output = draw(&mut screen, frame(10, 2, &["abc", "dxf"]));
+    let mut changes = Vec::new();
+    queue!(changes, cursor::MoveTo(1, 1)).unwrap();
+    changes.push(b'x');
+    assert_eq!(output, expected(&changes));
+}
+
+#[test]
+fn narrow_overwrite_clears_wide_continuation() {
+    let mut screen = Screen::new();
+    draw(&mut screen, frame(10, 1, &["世"]));
+    let output = draw(&mut screen, frame(10, 1, &["a"]));
+    // 宽字符的后续单元格输出为空格，否则终端上残留宽字符的右半部分
+    let mut changes = Vec::new();
+    queue!(changes, cursor::MoveTo(0, 0)).unwrap();
+    changes.extend_from_slice(b"a ");
+    assert_eq!(output, expected(&changes));
+}
+
+#[test]
+fn resize_forces_full_redraw() {
+    let mut screen = Screen::new();
+    draw(&mut screen, frame(10, 2, &["abc"]));
+    let output = draw(&mut screen, frame(12, 2, &["abc"]));
+    let mut clear = Vec::new();
+    queue!(clear, terminal::Clear(terminal::ClearType::All)).unwrap();
+    assert!(output.windows(clear.len()).any(|w| w == clear));
+    // 清屏后未变化的内容同样需要重新输出
+    assert!(output.windows(3).any(|w| w == b"abc"));
+}
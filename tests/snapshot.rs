@@ -0,0 +1,360 @@
+//! 渲染快照与按键回放测试。
+//!
+//! 使用内存后端创建编辑器，输入按键或回放 `tests/scripts` 下的按键脚本后，
+//! 将渲染结果与 `tests/snapshots` 下的快照文件比较。
+//! 设置环境变量 `UPDATE_SNAPSHOTS=1` 运行测试时以当前渲染结果覆盖快照文件
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use z_editor::replay::{KeyScript, Replay};
+use z_editor::{Editor, HeadlessBackend};
+
+// 测试窗口大小
+const WIDTH: usize = 40;
+const HEIGHT: usize = 10;
+
+// 测试用编辑器，持有与编辑器共享画面的内存后端
+struct Harness {
+    editor: Editor,
+    backend: HeadlessBackend,
+}
+
+impl Harness {
+    // 打开 tests/fixtures 下的文件，未指定文件时打开空缓冲区
+    fn open(fixture: Option<&str>) -> Self {
+        let backend = HeadlessBackend::new(WIDTH, HEIGHT);
+        let path = fixture.map(self::fixture);
+        let mut editor = Editor::open(path.as_deref(), Box::new(backend.clone())).unwrap();
+        editor.refresh_screen().unwrap();
+        Self { editor, backend }
+    }
+
+    // 输入一个按键并刷新屏幕
+    fn press(&mut self, code: KeyCode, modifiers: KeyModifiers) {
+        let exit = self
+            .editor
+            .handle_event(Event::Key(KeyEvent::new(code, modifiers)))
+            .unwrap();
+        assert!(!exit, "编辑器意外退出");
+        self.editor.refresh_screen().unwrap();
+    }
+
+    // 重复输入同一按键
+    fn press_times(&mut self, code: KeyCode, times: usize) {
+        for _ in 0..times {
+            self.press(code, KeyModifiers::NONE);
+        }
+    }
+
+    // 逐字符输入文本
+    fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            self.press(KeyCode::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    // 将画面与快照文件比较
+    fn assert_snapshot(&self, name: &str) {
+        assert_snapshot(name, &self.backend.snapshot());
+    }
+}
+
+// tests 目录
+fn tests_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests")
+}
+
+// tests/fixtures 下的文件
+fn fixture(name: &str) -> PathBuf {
+    tests_dir().join("fixtures").join(name)
+}
+
+// 回放按键脚本，未指定文件时打开空缓冲区
+fn replay(script: &str, file: Option<&str>) -> Replay {
+    let script = KeyScript::parse(script).unwrap();
+    script.replay(file.map(fixture).as_deref()).unwrap()
+}
+
+// 回放 tests/scripts 下的按键脚本
+fn replay_script(name: &str, file: Option<&str>) -> Replay {
+    let path = tests_dir().join("scripts").join(format!("{}.keys", name));
+    let script = KeyScript::from_file(&path).unwrap();
+    script.replay(file.map(fixture).as_deref()).unwrap()
+}
+
+// 将内容与快照文件比较
+fn assert_snapshot(name: &str, actual: &str) {
+    let path = tests_dir().join("snapshots").join(format!("{}.snap", name));
+    if env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "无法读取快照 {:?}：{}，可设置 UPDATE_SNAPSHOTS=1 生成\n{}",
+            path, e, actual
+        )
+    });
+    assert!(
+        expected == actual,
+        "内容与快照 {:?} 不一致\n--- 快照\n{}--- 实际\n{}",
+        path,
+        expected,
+        actual
+    );
+}
+
+#[test]
+fn empty_buffer_shows_banner() {
+    let harness = Harness::open(None);
+    assert!(harness
+        .backend
+        .status_bar()
+        .starts_with("[No Name] -- 0 lines"));
+    assert!(harness
+        .backend
+        .message_bar()
+        .starts_with("HELP: Ctrl-S = Save"));
+    harness.assert_snapshot("empty_buffer");
+}
+
+#[test]
+fn typing_marks_buffer_modified() {
+    let mut harness = Harness::open(None);
+    harness.type_text("hello");
+    harness.press(KeyCode::Enter, KeyModifiers::NONE);
+    harness.type_text("世界");
+    assert_eq!(harness.backend.row_text(1), "   2 世界");
+    assert_eq!(harness.backend.cursor(), Some((9, 1)));
+    assert!(harness.backend.status_bar().contains("(modified)"));
+    harness.assert_snapshot("typing");
+}
+
+#[test]
+fn open_file_renders_tabs_and_wide_characters() {
+    let mut harness = Harness::open(Some("sample.txt"));
+    harness.press(KeyCode::Down, KeyModifiers::NONE);
+    harness.press(KeyCode::End, KeyModifiers::NONE);
+    assert_eq!(harness.backend.cursor(), Some((36, 1)));
+    assert!(harness.backend.status_bar().ends_with("UTF-8 | LF | 2/3"));
+    harness.assert_snapshot("sample_file");
+}
+
+#[test]
+fn cursor_scrolls_past_last_screen_row() {
+    let mut harness = Harness::open(Some("lines.txt"));
+    harness.press_times(KeyCode::Down, 12);
+    assert_eq!(harness.backend.row_text(7), "  13 line 13");
+    assert_eq!(harness.backend.cursor(), Some((5, 7)));
+    harness.assert_snapshot("scrolled");
+}
+
+#[test]
+fn soft_wrap_keeps_wide_characters_whole() {
+    let mut harness = Harness::open(Some("long.txt"));
+    harness.press(KeyCode::Char('z'), KeyModifiers::ALT);
+    harness.assert_snapshot("soft_wrap");
+}
+
+#[test]
+fn soft_wrap_full_width_row_moves_cursor_to_next_line() {
+    let mut harness = Harness::open(None);
+    harness.press(KeyCode::Char('z'), KeyModifiers::ALT);
+    // 行号栏占 5 列，输入恰好占满文本区域的一行
+    harness.type_text(&"x".repeat(WIDTH - 5));
+    // 行末光标不能停留在文本区域之外，应显示在下一个屏幕行开头
+    assert_eq!(harness.backend.cursor(), Some((5, 1)));
+    harness.assert_snapshot("soft_wrap_full_width");
+}
+
+#[test]
+fn prompt_is_shown_in_message_bar() {
+    let mut harness = Harness::open(Some("sample.txt"));
+    harness.press(KeyCode::Char('r'), KeyModifiers::ALT);
+    assert!(harness
+        .backend
+        .message_bar()
+        .starts_with("以指定编码重新打开"));
+    harness.type_text("gbk");
+    harness.press(KeyCode::Esc, KeyModifiers::NONE);
+    assert_eq!(harness.backend.message_bar(), "已取消");
+}
+
+#[test]
+fn saving_unnamed_buffer_prompts_for_file_name() {
+    let path = env::temp_dir().join(format!("z-editor-save-as-{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let mut harness = Harness::open(None);
+    harness.type_text("hi");
+    harness.press(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.message_bar(), "另存为：");
+    harness.type_text(path.to_str().unwrap());
+    harness.press(KeyCode::Enter, KeyModifiers::NONE);
+    assert_eq!(fs::read_to_string(&path).unwrap(), "hi");
+    let name = path.file_name().unwrap().to_str().unwrap();
+    assert!(harness.backend.status_bar().starts_with(name));
+
+    // 不覆盖已存在的文件
+    let mut harness = Harness::open(None);
+    harness.press(KeyCode::Char('s'), KeyModifiers::CONTROL);
+    harness.type_text(path.to_str().unwrap());
+    harness.press(KeyCode::Enter, KeyModifiers::NONE);
+    assert!(harness.backend.message_bar().starts_with("文件已存在"));
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn log_viewer_returns_to_file_and_cursor() {
+    let mut harness = Harness::open(Some("sample.txt"));
+    harness.press(KeyCode::Down, KeyModifiers::NONE);
+    harness.press(KeyCode::End, KeyModifiers::NONE);
+    harness.press(KeyCode::F(12), KeyModifiers::NONE);
+    assert!(harness.backend.status_bar().starts_with("[log]"));
+    assert!(harness.backend.status_bar().contains("(read-only)"));
+    harness.press(KeyCode::F(12), KeyModifiers::NONE);
+    assert!(harness.backend.status_bar().starts_with("sample.txt"));
+    assert_eq!(harness.backend.cursor(), Some((36, 1)));
+}
+
+#[test]
+fn goto_line_centers_target() {
+    let mut harness = Harness::open(Some("lines.txt"));
+    let goto = |harness: &mut Harness, input: &str| {
+        harness.press(KeyCode::Char('g'), KeyModifiers::CONTROL);
+        harness.type_text(input);
+        harness.press(KeyCode::Enter, KeyModifiers::NONE);
+    };
+    goto(&mut harness, "20:4");
+    harness.assert_snapshot("goto_centered");
+
+    goto(&mut harness, "+3");
+    assert!(harness.backend.status_bar().ends_with("23/30"));
+    goto(&mut harness, "-100");
+    assert!(harness.backend.status_bar().ends_with("1/30"));
+    goto(&mut harness, "50%");
+    assert!(harness.backend.status_bar().ends_with("15/30"));
+    goto(&mut harness, "999");
+    assert!(harness.backend.status_bar().ends_with("30/30"));
+    assert_eq!(harness.backend.message_bar(), "已跳转到第 30 行");
+
+    goto(&mut harness, "abc");
+    assert_eq!(harness.backend.message_bar(), "无效的跳转位置：abc");
+    goto(&mut harness, "0");
+    assert_eq!(harness.backend.message_bar(), "行号与列号从 1 开始");
+    assert!(harness.backend.status_bar().ends_with("30/30"));
+}
+
+#[test]
+fn word_motions_follow_unicode_boundaries() {
+    let mut harness = Harness::open(Some("motions.txt"));
+    harness.press(KeyCode::Down, KeyModifiers::NONE);
+    let mut stops = Vec::new();
+    for _ in 0..6 {
+        harness.press(KeyCode::Right, KeyModifiers::CONTROL);
+        stops.push(harness.backend.cursor().unwrap().0);
+    }
+    // let、名、字、=、vec、!
+    assert_eq!(stops, [9, 13, 15, 18, 20, 23]);
+    harness.press(KeyCode::Left, KeyModifiers::CONTROL);
+    harness.press(KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((18, 1)));
+
+    // 行首向前移动至上一行行末，行末向后移动至下一行行首
+    harness.press(KeyCode::Home, KeyModifiers::NONE);
+    harness.press(KeyCode::Left, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((16, 0)));
+    harness.press(KeyCode::Right, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((5, 1)));
+}
+
+#[test]
+fn paragraph_and_buffer_motions() {
+    let mut harness = Harness::open(Some("motions.txt"));
+    harness.press(KeyCode::Down, KeyModifiers::CONTROL);
+    assert!(harness.backend.status_bar().ends_with("4/5"));
+    harness.press(KeyCode::Down, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((27, 4)));
+    harness.press(KeyCode::Up, KeyModifiers::CONTROL);
+    assert!(harness.backend.status_bar().ends_with("4/5"));
+    harness.press(KeyCode::Up, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((5, 0)));
+
+    harness.press(KeyCode::End, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((27, 4)));
+    harness.press(KeyCode::Home, KeyModifiers::CONTROL);
+    assert_eq!(harness.backend.cursor(), Some((5, 0)));
+}
+
+#[test]
+fn matching_bracket_spans_lines() {
+    let mut harness = Harness::open(Some("motions.txt"));
+    harness.press(KeyCode::End, KeyModifiers::NONE);
+    harness.press(KeyCode::Left, KeyModifiers::NONE);
+    harness.press(KeyCode::Char('m'), KeyModifiers::ALT);
+    assert_eq!(harness.backend.cursor(), Some((5, 2)));
+    harness.press(KeyCode::Char('m'), KeyModifiers::ALT);
+    assert_eq!(harness.backend.cursor(), Some((15, 0)));
+
+    // 嵌套括号
+    harness.press(KeyCode::Down, KeyModifiers::NONE);
+    harness.press(KeyCode::End, KeyModifiers::NONE);
+    harness.press_times(KeyCode::Left, 2);
+    harness.press(KeyCode::Char('m'), KeyModifiers::ALT);
+    assert_eq!(harness.backend.cursor(), Some((24, 1)));
+
+    harness.press(KeyCode::Home, KeyModifiers::NONE);
+    harness.press(KeyCode::Char('m'), KeyModifiers::ALT);
+    assert_eq!(harness.backend.message_bar(), "光标处没有可匹配的括号");
+}
+
+#[test]
+fn binary_file_opens_in_hex_view() {
+    let harness = Harness::open(Some("binary.bin"));
+    assert!(harness.backend.status_bar().contains("48 bytes [hex]"));
+    harness.assert_snapshot("hex_view");
+}
+
+#[test]
+fn left_at_start_of_file_stays_put() {
+    let replay = replay_script("left_at_start", Some("sample.txt"));
+    assert!(replay.text().starts_with(">fn main() {|\n"));
+    assert_snapshot("left_at_start", &replay.to_string());
+}
+
+#[test]
+fn end_past_last_row_stays_put() {
+    let replay = replay_script("end_past_last_row", Some("no_final_newline.txt"));
+    assert_eq!(replay.text(), "abc\n!");
+    assert_snapshot("end_past_last_row", &replay.to_string());
+}
+
+#[test]
+fn left_at_start_of_empty_buffer_stays_put() {
+    let replay = replay("Left\nBackspace\nUp\ntype a", None);
+    assert_eq!(replay.text(), "a");
+}
+
+#[test]
+fn left_at_line_start_moves_to_previous_line_end() {
+    let replay = replay("Down\nLeft\ntype ;", Some("sample.txt"));
+    assert!(replay.text().starts_with("fn main() {;\n"));
+}
+
+#[test]
+fn quit_stops_replay() {
+    let replay = replay("type a\nCtrl-q\ntype b", None);
+    assert_eq!(replay.text(), "a");
+    assert!(replay.to_string().contains("--- screen (exited)"));
+}
+
+#[test]
+fn invalid_key_script_reports_line() {
+    let error = KeyScript::parse("Left\nCtrl-Foo").err().unwrap();
+    assert!(error.starts_with("第 2 行"), "{}", error);
+    assert!(KeyScript::parse("Left*x").is_err());
+    assert!(KeyScript::parse("size 40").is_err());
+}
@@ -0,0 +1,87 @@
+//! 交换文件的测试
+
+use std::env;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::process::{self, Command};
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use z_editor::cli::FileArg;
+use z_editor::{Editor, HeadlessBackend};
+
+// 打开文件并输入按键。Editor::open 不检查交换文件，
+// 因此先打开一个空的占位文件，再按 Alt-N 切换到被测文件
+fn open(file: PathBuf, keys: &[(KeyCode, KeyModifiers)]) -> Editor {
+    let placeholder = file.with_file_name("placeholder.txt");
+    fs::write(&placeholder, "").unwrap();
+    let files = vec![
+        FileArg::new(Some(placeholder), None, None),
+        FileArg::new(Some(file), None, None),
+    ];
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open_files(files, false, Box::new(backend)).unwrap();
+    press(&mut editor, KeyCode::Char('n'), KeyModifiers::ALT);
+    for &(code, modifiers) in keys {
+        press(&mut editor, code, modifiers);
+    }
+    editor
+}
+
+// 输入一个按键
+fn press(editor: &mut Editor, code: KeyCode, modifiers: KeyModifiers) {
+    let event = Event::Key(KeyEvent::new(code, modifiers));
+    assert!(!editor.handle_event(event).unwrap());
+}
+
+#[test]
+fn kept_stale_swap_falls_back_to_alternate() {
+    let dir = env::temp_dir().join(format!("z-editor-swap-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("a.txt");
+    fs::write(&file, "abc\n").unwrap();
+    // 文件头无法识别，视为过期的交换文件
+    let stale = dir.join(".a.txt.swp");
+    fs::write(&stale, "stale").unwrap();
+
+    let keys = [
+        (KeyCode::Esc, KeyModifiers::NONE),
+        (KeyCode::Char('x'), KeyModifiers::NONE),
+    ];
+    let editor = open(file, &keys);
+    let swap = editor.write_emergency_copy().unwrap();
+    assert_eq!(swap, dir.join(format!(".a.txt.{}.swp", process::id())));
+    assert!(fs::read_to_string(&swap).unwrap().ends_with("xabc\n"));
+    // 交换文件保存了未保存的内容，仅允许所有者读写
+    let mode = fs::metadata(&swap).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    assert_eq!(fs::read_to_string(&stale).unwrap(), "stale");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn swap_of_reused_pid_is_stale() {
+    let dir = env::temp_dir().join(format!("z-editor-swap-reuse-{}", process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("b.txt");
+    fs::write(&file, "abc\n").unwrap();
+    // 进程号属于正在运行的进程，但启动时间不同，说明写入者已退出且进程号被复用
+    let mut other = Command::new("sleep").arg("10").spawn().unwrap();
+    let header = format!("z-editor swap\npid: {}\nstart: 1\n\nold\n", other.id());
+    fs::write(dir.join(".b.txt.swp"), header).unwrap();
+
+    // 提示恢复过期的交换文件，选择删除后继续使用原交换文件
+    let keys = [
+        (KeyCode::Char('x'), KeyModifiers::NONE),
+        (KeyCode::Enter, KeyModifiers::NONE),
+        (KeyCode::Char('y'), KeyModifiers::NONE),
+    ];
+    let editor = open(file, &keys);
+    other.kill().unwrap();
+    other.wait().unwrap();
+    let swap = editor.write_emergency_copy().unwrap();
+    assert_eq!(swap, dir.join(".b.txt.swp"));
+    assert!(fs::read_to_string(&swap).unwrap().ends_with("yabc\n"));
+    fs::remove_dir_all(&dir).unwrap();
+}
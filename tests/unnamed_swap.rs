@@ -0,0 +1,34 @@
+//! 未命名缓冲区交换文件的测试。
+//!
+//! 测试需要修改环境变量 `XDG_STATE_HOME`，与其他测试并行运行时会相互干扰，因此单独作为一个测试程序
+
+use std::env;
+use std::fs;
+use std::process;
+
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+
+use z_editor::{Editor, HeadlessBackend};
+
+// 输入一个按键
+fn press(editor: &mut Editor, code: KeyCode, modifiers: KeyModifiers) {
+    let event = Event::Key(KeyEvent::new(code, modifiers));
+    assert!(!editor.handle_event(event).unwrap());
+}
+
+#[test]
+fn unnamed_swap_is_per_process() {
+    let state = env::temp_dir().join(format!("z-editor-state-{}", process::id()));
+    env::set_var("XDG_STATE_HOME", &state);
+    let backend = HeadlessBackend::new(40, 10);
+    let mut editor = Editor::open(None, Box::new(backend)).unwrap();
+    // 打开并关闭日志查看器时检查交换文件
+    press(&mut editor, KeyCode::F(12), KeyModifiers::NONE);
+    press(&mut editor, KeyCode::F(12), KeyModifiers::NONE);
+    press(&mut editor, KeyCode::Char('x'), KeyModifiers::NONE);
+    let swap = editor.write_emergency_copy().unwrap();
+    let name = format!(".noname.{}.swp", process::id());
+    assert_eq!(swap, state.join("z-editor").join(name));
+    assert!(fs::read_to_string(&swap).unwrap().ends_with('x'));
+    fs::remove_dir_all(&state).unwrap();
+}